@@ -0,0 +1,216 @@
+use crate::{
+    cli::{split_address, BatchSource},
+    config::Config,
+    json::{error_to_json, status_to_json},
+};
+
+use colored::{ColoredString, Colorize};
+use motd::{query, text::truncate, tr, Edition, QueryError, QueryOptions, ServerStatus};
+use serde_json::Value;
+use unicode_width::UnicodeWidthStr;
+
+use std::{
+    fs,
+    io::{self, Read},
+    process::exit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+pub struct Server {
+    /// 原始的一行，作为表格和指标中的名称
+    pub line: String,
+    pub addr: String,
+    pub port: Option<u16>,
+}
+
+impl Server {
+    /// 使用这一行指定的端口查询
+    pub fn query(&self, options: &QueryOptions) -> Result<ServerStatus, QueryError> {
+        let options = QueryOptions {
+            port: self.port,
+            ..options.clone()
+        };
+        query(&self.addr, &options)
+    }
+}
+
+pub fn run(
+    source: &BatchSource,
+    parallel: usize,
+    json: bool,
+    options: &QueryOptions,
+    config: &Config,
+) {
+    let servers = read_servers(source, config);
+    let results = query_all(&servers, parallel, |server| server.query(options));
+    let failed = results.iter().any(Result::is_err);
+
+    if json {
+        let documents: Vec<Value> = servers
+            .iter()
+            .zip(&results)
+            .map(|(server, result)| match result {
+                Ok(status) => status_to_json(&server.addr, status),
+                Err(e) => error_to_json(&server.addr, e),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&documents).unwrap());
+    } else {
+        print_table(&servers, &results);
+    }
+    if failed {
+        exit(1);
+    }
+}
+
+/// 每行一个地址或别名，`#` 开头为注释
+pub fn read_servers(source: &BatchSource, config: &Config) -> Vec<Server> {
+    let content = match source {
+        BatchSource::File(path) if path == "-" => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content).map(|_| content)
+        }
+        BatchSource::File(path) => fs::read_to_string(path),
+        BatchSource::List(servers) => Ok(servers.join("\n")),
+    };
+    let content = content.unwrap_or_else(|e| {
+        println!(
+            "{}\n{}",
+            tr!("无法读取服务器列表").bright_red().bold(),
+            e.to_string().bright_red()
+        );
+        exit(1);
+    });
+
+    let mut servers = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match split_address(config.resolve(line)) {
+            Some((addr, port)) => servers.push(Server {
+                line: line.to_string(),
+                addr,
+                port,
+            }),
+            None => {
+                println!(
+                    "{}\n{} {}",
+                    tr!("第 {} 行的端口号不合法", index + 1).bright_red().bold(),
+                    "-->".bright_cyan().bold(),
+                    line.bright_yellow()
+                );
+                exit(1);
+            }
+        }
+    }
+    servers
+}
+
+/// 最多同时对 `parallel` 个服务器执行 `work`，结果的顺序与 `servers` 一致
+pub fn query_all<T: Send>(
+    servers: &[Server],
+    parallel: usize,
+    work: impl Fn(&Server) -> T + Sync,
+) -> Vec<T> {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<T>>> = Mutex::new(servers.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..parallel.min(servers.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(server) = servers.get(index) else {
+                    break;
+                };
+                let result = work(server);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(Option::unwrap)
+        .collect()
+}
+
+fn print_table(servers: &[Server], results: &[Result<ServerStatus, QueryError>]) {
+    let header = [
+        tr!("地址"),
+        tr!("版本"),
+        tr!("游戏版本"),
+        tr!("玩家"),
+        tr!("延迟"),
+    ];
+    let rows: Vec<[String; 5]> = servers
+        .iter()
+        .zip(results)
+        .map(|(server, result)| match result {
+            Ok(status) => [
+                server.line.clone(),
+                match status.edition {
+                    Edition::Java => tr!("Java版").to_string(),
+                    Edition::Bedrock => tr!("基岩版").to_string(),
+                },
+                truncate(&status.version, 30),
+                format!("{} / {}", status.players_online, status.players_max),
+                status
+                    .latency
+                    .map(|latency| format!("{} ms", latency.as_millis()))
+                    .unwrap_or_else(|| "-".to_string()),
+            ],
+            Err(e) => [
+                server.line.clone(),
+                tr!("失败").to_string(),
+                truncate(&e.to_string(), 30),
+                String::new(),
+                String::new(),
+            ],
+        })
+        .collect();
+
+    let mut widths = header.map(UnicodeWidthStr::width);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let header: Vec<String> = header
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| pad(cell, width).bright_cyan().bold().to_string())
+        .collect();
+    println!("{}", header.join(" | "));
+    for (row, result) in rows.iter().zip(results) {
+        let cells: Vec<ColoredString> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                let cell = pad(cell, width);
+                match (column, result) {
+                    (0, Err(_)) => cell.bright_red(),
+                    (1, Err(_)) | (2, Err(_)) => cell.bright_red().bold(),
+                    (1, Ok(_)) => cell.bright_green(),
+                    (2, Ok(_)) => cell.bright_yellow(),
+                    _ => cell.normal(),
+                }
+            })
+            .collect();
+        let line: Vec<String> = cells.iter().map(ColoredString::to_string).collect();
+        println!("{}", line.join(" | "));
+    }
+}
+
+fn pad(cell: &str, width: usize) -> String {
+    format!("{}{}", cell, " ".repeat(width - cell.width()))
+}
@@ -0,0 +1,175 @@
+use crate::{
+    net::{connect_udp, invalid_data},
+    status::GameMode,
+    tr,
+};
+
+use std::{
+    io,
+    net::SocketAddr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+const UNCONNECTED_PING: u8 = 0x01;
+const UNCONNECTED_PONG: u8 = 0x1c;
+/// RakNet 离线消息的固定魔数
+const MAGIC: [u8; 16] = [
+    0x00, 0xff, 0xff, 0x00, 0xfe, 0xfe, 0xfe, 0xfe, 0xfd, 0xfd, 0xfd, 0xfd, 0x12, 0x34, 0x56, 0x78,
+];
+
+pub struct BedrockPing {
+    pub motd: String,
+    pub protocol: i32,
+    pub version: String,
+    pub players_online: u32,
+    pub players_max: u32,
+    /// 存档名称
+    pub map: Option<String>,
+    pub game_mode: Option<GameMode>,
+    pub latency: Duration,
+}
+
+/// 发送 RakNet Unconnected Ping 并解析服务器的广播信息
+pub fn ping(address: SocketAddr, timeout: Duration) -> io::Result<BedrockPing> {
+    let socket = connect_udp(address, timeout)?;
+
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or_default();
+    let mut request = vec![UNCONNECTED_PING];
+    request.extend_from_slice(&time.to_be_bytes());
+    request.extend_from_slice(&MAGIC);
+    // 客户端 GUID
+    request.extend_from_slice(&time.rotate_left(32).to_be_bytes());
+
+    let start = Instant::now();
+    socket.send(&request)?;
+    let mut buf = [0u8; 2048];
+    loop {
+        let len = socket.recv(&mut buf)?;
+        let resp = &buf[..len];
+        // 丢弃不属于本次请求的数据包
+        if len >= 35 && resp[0] == UNCONNECTED_PONG && resp[1..9] == time.to_be_bytes() {
+            let latency = start.elapsed();
+            if resp[17..33] != MAGIC {
+                return Err(invalid_data(tr!("Unconnected Pong 的魔数不正确")));
+            }
+            let info_len = u16::from_be_bytes([resp[33], resp[34]]) as usize;
+            let info = resp
+                .get(35..35 + info_len)
+                .ok_or_else(|| invalid_data(tr!("Unconnected Pong 不完整")))?;
+            return parse_info(&String::from_utf8_lossy(info), latency);
+        }
+    }
+}
+
+/// 格式为 `MCPE;Motd;协议版本;游戏版本;在线人数;最大人数;服务器 ID;存档名称;游戏模式;...`
+fn parse_info(info: &str, latency: Duration) -> io::Result<BedrockPing> {
+    let fields: Vec<&str> = info.split(';').collect();
+    if fields.len() < 6 {
+        return Err(invalid_data(tr!("服务器信息的字段数量不正确")));
+    }
+    let number = |field: &str| {
+        field
+            .parse::<u32>()
+            .map_err(|_| invalid_data(tr!("服务器信息中的人数不合法")))
+    };
+    Ok(BedrockPing {
+        motd: fields[1].to_string(),
+        protocol: fields[2].parse().unwrap_or(-1),
+        version: fields[3].to_string(),
+        players_online: number(fields[4])?,
+        players_max: number(fields[5])?,
+        map: fields
+            .get(7)
+            .filter(|map| !map.is_empty())
+            .map(|map| map.to_string()),
+        game_mode: fields
+            .get(8)
+            .and_then(|game_mode| game_mode_by_name(game_mode)),
+        latency,
+    })
+}
+
+fn game_mode_by_name(name: &str) -> Option<GameMode> {
+    match name.to_ascii_lowercase().as_str() {
+        "survival" => Some(GameMode::Survival),
+        "creative" => Some(GameMode::Creative),
+        "hardcore" => Some(GameMode::Hardcore),
+        "spectator" => Some(GameMode::Spectator),
+        "adventure" => Some(GameMode::Adventure),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{net::UdpSocket, thread};
+
+    /// BDS 1.20.51 的服务器信息
+    const INFO: &str =
+        "MCPE;§eDedicated Server;630;1.20.51;3;10;13253860892328930865;Bedrock level;Survival;1;19132;19133;";
+
+    /// 以 `info` 回应一次 Unconnected Ping，Pong 只发送前 `limit` 个字节
+    fn stub_server(info: &str, limit: usize) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let info = info.as_bytes().to_vec();
+        thread::spawn(move || {
+            let mut buf = [0u8; 64];
+            let (_, peer) = socket.recv_from(&mut buf).unwrap();
+            let mut pong = vec![UNCONNECTED_PONG];
+            pong.extend_from_slice(&buf[1..9]);
+            pong.extend_from_slice(&0x1234u64.to_be_bytes());
+            pong.extend_from_slice(&MAGIC);
+            pong.extend_from_slice(&(info.len() as u16).to_be_bytes());
+            pong.extend_from_slice(&info);
+            pong.truncate(limit);
+            socket.send_to(&pong, peer).unwrap();
+        });
+        address
+    }
+
+    #[test]
+    fn parse_dedicated_server() {
+        let ping = parse_info(INFO, Duration::ZERO).unwrap();
+        assert_eq!(ping.motd, "§eDedicated Server");
+        assert_eq!(ping.protocol, 630);
+        assert_eq!(ping.version, "1.20.51");
+        assert_eq!((ping.players_online, ping.players_max), (3, 10));
+        assert_eq!(ping.map.as_deref(), Some("Bedrock level"));
+        assert_eq!(ping.game_mode, Some(GameMode::Survival));
+    }
+
+    #[test]
+    fn parse_minimal_info() {
+        let ping = parse_info("MCPE;Server;0;;0;20", Duration::ZERO).unwrap();
+        assert_eq!(ping.map, None);
+        assert_eq!(ping.game_mode, None);
+    }
+
+    #[test]
+    fn parse_truncated_info() {
+        // 截断在最大人数之前时字段不足或人数不合法
+        let end = INFO.match_indices(';').nth(4).unwrap().0 + 1;
+        for (len, _) in INFO[..end].char_indices() {
+            assert!(parse_info(&INFO[..len], Duration::ZERO).is_err(), "{}", len);
+        }
+    }
+
+    #[test]
+    fn ping_stub_server() {
+        let address = stub_server(INFO, usize::MAX);
+        let ping = ping(address, Duration::from_secs(2)).unwrap();
+        assert_eq!(ping.version, "1.20.51");
+    }
+
+    #[test]
+    fn ping_truncated_pong() {
+        let address = stub_server(INFO, 60);
+        assert!(ping(address, Duration::from_secs(2)).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use embedded_graphics::{
+    image::GetPixel,
+    mono_font::{iso_8859_1::FONT_5X8, MonoFont},
+    pixelcolor::BinaryColor,
+    prelude::{OriginDimensions, Point},
+};
+use image::{
+    imageops::{self, FilterType},
+    load_from_memory, ImageFormat, Rgba, RgbaImage,
+};
+use motd::{
+    text::{legacy_spans, Span, Style},
+    tr, ServerStatus,
+};
+use unicode_width::UnicodeWidthChar;
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    path::Path,
+    time::Duration,
+};
+
+/// 与原版多人游戏列表中的条目尺寸一致，四周各留 2 像素
+const WIDTH: u32 = 305;
+const HEIGHT: u32 = 36;
+const PADDING: i32 = 2;
+const ICON_SIZE: u32 = 32;
+/// 导出时放大的倍数，便于在聊天软件中查看
+const SCALE: u32 = 2;
+const LINE_HEIGHT: i32 = 9;
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const ICON_PLACEHOLDER: Rgba<u8> = Rgba([64, 64, 64, 255]);
+/// 原版中 Motd 未指定颜色时使用的灰色
+const MOTD_COLOR: (u8, u8, u8) = (128, 128, 128);
+const BAR_ACTIVE: Rgba<u8> = Rgba([85, 255, 85, 255]);
+const BAR_INACTIVE: Rgba<u8> = Rgba([85, 85, 85, 255]);
+const BAR_HEIGHTS: [u32; 5] = [2, 3, 5, 6, 8];
+const FONT: MonoFont = FONT_5X8;
+
+/// 仿照游戏内的多人游戏列表，将服务器绘制为一个条目并保存为 PNG
+///
+/// 内置字体只包含 ISO-8859-1 字符，有字符超出字体范围时不保存图片，避免生成满是方框的图片
+pub fn render_png(status: &ServerStatus, name: &str, path: &Path) -> Result<(), String> {
+    let mut card = RgbaImage::from_pixel(WIDTH, HEIGHT, BACKGROUND);
+    draw_icon(&mut card, status)?;
+
+    let left = PADDING + ICON_SIZE as i32 + 3;
+    let right = WIDTH as i32 - PADDING;
+    // 人数显示在信号图标左侧，与原版一样右对齐
+    let players = legacy_spans(
+        &format!("§7{}§8/§7{}", status.players_online, status.players_max),
+        &Style::default(),
+        status.edition,
+    );
+    let players_x = right - 15 - 2 - text_width(&players);
+    draw_signal(&mut card, right - 15, PADDING, status.latency);
+    let mut missing = draw_spans(&mut card, &players, players_x, PADDING + 1, right - 15, 1);
+
+    let name = legacy_spans(
+        name,
+        &Style {
+            color: Some((255, 255, 255)),
+            ..Style::default()
+        },
+        status.edition,
+    );
+    missing += draw_spans(&mut card, &name, left, PADDING + 1, players_x - 4, 1);
+
+    let mut motd = status.motd_spans();
+    for span in &mut motd {
+        span.style.color = span.style.color.or(Some(MOTD_COLOR));
+    }
+    missing += draw_spans(&mut card, &motd, left, PADDING + 12, right, 2);
+    if missing > 0 {
+        return Err(tr!(
+            "{} 个字符超出内置字体的范围，无法绘制图片，请使用文本输出或 --export-motd",
+            missing
+        ));
+    }
+
+    let card = imageops::resize(&card, WIDTH * SCALE, HEIGHT * SCALE, FilterType::Nearest);
+    card.save_with_format(path, ImageFormat::Png)
+        .map_err(|e| tr!("无法写入 {}: {}", path.display(), e))
+}
+
+/// 没有图标时与原版一样显示一个占位方块
+fn draw_icon(card: &mut RgbaImage, status: &ServerStatus) -> Result<(), String> {
+    let icon = match status.favicon_png() {
+        Some(png) => {
+            let png = png.map_err(|e| tr!("图标数据损坏，{}", e))?;
+            load_from_memory(&png)
+                .map_err(|e| tr!("图标数据损坏，{}", e))?
+                .resize_exact(ICON_SIZE, ICON_SIZE, FilterType::Triangle)
+                .to_rgba8()
+        }
+        None => RgbaImage::from_pixel(ICON_SIZE, ICON_SIZE, ICON_PLACEHOLDER),
+    };
+    imageops::overlay(card, &icon, PADDING as i64, PADDING as i64);
+    Ok(())
+}
+
+/// 五格信号，延迟划分与原版一致，没有延迟数据时全部显示为灰色
+fn draw_signal(card: &mut RgbaImage, x: i32, y: i32, latency: Option<Duration>) {
+    let bars = match latency.map(|latency| latency.as_millis()) {
+        None => 0,
+        Some(0..=149) => 5,
+        Some(150..=299) => 4,
+        Some(300..=599) => 3,
+        Some(600..=999) => 2,
+        Some(_) => 1,
+    };
+    for (index, height) in BAR_HEIGHTS.into_iter().enumerate() {
+        let color = if index < bars {
+            BAR_ACTIVE
+        } else {
+            BAR_INACTIVE
+        };
+        for dy in 0..height {
+            put_pixel(card, x + index as i32 * 2, y + 8 - 1 - dy as i32, color);
+        }
+    }
+}
+
+/// 从 (`x`, `y`) 开始绘制，超出 `limit` 时换行，最多绘制 `max_lines` 行
+///
+/// 返回绘制为方框的字符数
+fn draw_spans(
+    card: &mut RgbaImage,
+    spans: &[Span],
+    x: i32,
+    y: i32,
+    limit: i32,
+    max_lines: i32,
+) -> usize {
+    let (mut cursor, mut line, mut missing) = (x, 0, 0);
+    for span in spans {
+        let style = &span.style;
+        let (r, g, b) = style.color.unwrap_or((255, 255, 255));
+        let color = Rgba([r, g, b, 255]);
+        // 原版的文字阴影为文字颜色的四分之一亮度
+        let shadow = Rgba([r / 4, g / 4, b / 4, 255]);
+        for c in span.text.chars() {
+            let c = if style.obfuscated { obfuscate(c) } else { c };
+            let width = advance(c, style.bold);
+            if c == '\n' || cursor + width > limit {
+                line += 1;
+                cursor = x;
+                if line >= max_lines {
+                    return missing;
+                }
+                if c == '\n' {
+                    continue;
+                }
+            }
+            let top = y + line * LINE_HEIGHT;
+            draw_char(card, c, cursor + 1, top + 1, style, shadow);
+            draw_char(card, c, cursor, top, style, color);
+            cursor += width;
+            if c != ' ' && glyph(c).is_none() {
+                missing += 1;
+            }
+        }
+    }
+    missing
+}
+
+fn draw_char(card: &mut RgbaImage, c: char, x: i32, y: i32, style: &Style, color: Rgba<u8>) {
+    let width = advance(c, style.bold);
+    match glyph(c) {
+        Some(rows) => {
+            for (row, bits) in rows.iter().enumerate() {
+                // 斜体将上半部分向右错开一个像素
+                let shift = if style.italic && row < 4 { 1 } else { 0 };
+                for column in 0..FONT.character_size.width as i32 {
+                    if bits & (1 << column) == 0 {
+                        continue;
+                    }
+                    let px = x + column + shift;
+                    put_pixel(card, px, y + row as i32, color);
+                    if style.bold {
+                        put_pixel(card, px + 1, y + row as i32, color);
+                    }
+                }
+            }
+        }
+        // 字体中没有的字符与原版一样显示为方框
+        None => {
+            let right = x + width - 2;
+            for px in x..=right {
+                put_pixel(card, px, y, color);
+                put_pixel(card, px, y + 7, color);
+            }
+            for py in y..y + 8 {
+                put_pixel(card, x, py, color);
+                put_pixel(card, right, py, color);
+            }
+        }
+    }
+    if style.underlined {
+        for px in x - 1..x + width {
+            put_pixel(card, px, y + 8, color);
+        }
+    }
+    if style.strikethrough {
+        for px in x - 1..x + width {
+            put_pixel(card, px, y + 3, color);
+        }
+    }
+}
+
+/// 字形每行的像素，第 n 位表示第 n 列，字体中没有该字符时返回 `None`
+fn glyph(c: char) -> Option<Vec<u32>> {
+    if !(' '..='\u{ff}').contains(&c) || c.is_control() {
+        return None;
+    }
+    let size = FONT.character_size;
+    let per_row = FONT.image.size().width / size.width;
+    let index = FONT.glyph_mapping.index(c) as u32;
+    let (left, top) = (
+        (index % per_row) * size.width,
+        (index / per_row) * size.height,
+    );
+    let rows = (0..size.height)
+        .map(|row| {
+            (0..size.width)
+                .filter(|&column| {
+                    let point = Point::new((left + column) as i32, (top + row) as i32);
+                    FONT.image.pixel(point) == Some(BinaryColor::On)
+                })
+                .fold(0, |bits, column| bits | 1 << column)
+        })
+        .collect::<Vec<u32>>();
+    // 原版的字形靠左对齐，去掉左侧的空白列
+    let offset = rows
+        .iter()
+        .map(|bits| bits.trailing_zeros())
+        .min()
+        .unwrap_or(0);
+    Some(
+        rows.into_iter()
+            .map(|bits| bits.checked_shr(offset).unwrap_or(0))
+            .collect(),
+    )
+}
+
+/// 与原版的字体渲染一样，字宽为字形最右侧像素的位置加一像素间距，粗体再加一像素
+fn advance(c: char, bold: bool) -> i32 {
+    let width = match glyph(c) {
+        _ if c == ' ' => 4,
+        Some(rows) => {
+            let bits = rows.into_iter().fold(0, |all, bits| all | bits);
+            32 - bits.leading_zeros() as i32 + 1
+        }
+        None => c.width().unwrap_or(1).max(1) as i32 * 4 + 2,
+    };
+    width + if bold { 1 } else { 0 }
+}
+
+fn text_width(spans: &[Span]) -> i32 {
+    spans
+        .iter()
+        .flat_map(|span| span.text.chars().map(|c| advance(c, span.style.bold)))
+        .sum()
+}
+
+/// 乱码文本替换为宽度相同的随机字符
+fn obfuscate(c: char) -> char {
+    let width = advance(c, false);
+    let candidates: Vec<char> = ('!'..='~')
+        .filter(|&candidate| advance(candidate, false) == width)
+        .collect();
+    if candidates.is_empty() {
+        return c;
+    }
+    let random = RandomState::new().build_hasher().finish();
+    candidates[(random % candidates.len() as u64) as usize]
+}
+
+fn put_pixel(card: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < card.width() && (y as u32) < card.height() {
+        card.put_pixel(x as u32, y as u32, color);
+    }
+}
@@ -0,0 +1,261 @@
+use motd::{
+    i18n::{set_language, Language},
+    query, QueryOptions, ServerStatus,
+};
+use regex::Regex;
+
+use std::{process::exit, time::Duration};
+
+/// `check` 模式的阈值，超过阈值即为对应的状态
+#[derive(Default)]
+pub struct Thresholds {
+    pub warning_latency: Option<Duration>,
+    pub critical_latency: Option<Duration>,
+    /// 在线人数占上限的比例，0 - 1
+    pub warning_fill: Option<f64>,
+    pub critical_fill: Option<f64>,
+    /// 版本不匹配时为 WARNING
+    pub version: Option<Regex>,
+}
+
+/// Nagios 插件规范中的状态，顺序即严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum State {
+    Ok,
+    Warning,
+    Critical,
+}
+
+impl State {
+    fn name(self) -> &'static str {
+        match self {
+            State::Ok => "OK",
+            State::Warning => "WARNING",
+            State::Critical => "CRITICAL",
+        }
+    }
+
+    fn exit_code(self) -> i32 {
+        match self {
+            State::Ok => 0,
+            State::Warning => 1,
+            State::Critical => 2,
+        }
+    }
+}
+
+/// 输出一行摘要与性能数据，并以插件规范的退出码退出，查询失败时为 CRITICAL
+pub fn run(addr: &str, port: Option<u16>, thresholds: &Thresholds, options: &QueryOptions) -> ! {
+    // 输出由监控系统解析与展示，固定为英文，不随界面语言变化
+    set_language(Language::EnUs);
+    let options = QueryOptions {
+        port,
+        ..options.clone()
+    };
+    let status = match query(addr, &options) {
+        Ok(status) => status,
+        Err(e) => {
+            println!("MOTD {} - {}", State::Critical.name(), e);
+            exit(State::Critical.exit_code());
+        }
+    };
+
+    let (state, problems) = evaluate(&status, thresholds);
+    let summary = if problems.is_empty() {
+        summary(&status)
+    } else {
+        problems.join(", ")
+    };
+    println!(
+        "MOTD {} - {} | {}",
+        state.name(),
+        summary,
+        perfdata(&status, thresholds)
+    );
+    exit(state.exit_code());
+}
+
+/// 返回最严重的状态与超出阈值的各项说明
+fn evaluate(status: &ServerStatus, thresholds: &Thresholds) -> (State, Vec<String>) {
+    let mut state = State::Ok;
+    let mut problems = Vec::new();
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+
+    if let Some(latency) = status.latency.map(millis) {
+        let warning = thresholds.warning_latency.map(millis);
+        let critical = thresholds.critical_latency.map(millis);
+        if let Some((level, threshold)) = exceeded(latency, warning, critical) {
+            state = state.max(level);
+            problems.push(format!(
+                "latency {} ms exceeds {} ms",
+                round(latency),
+                threshold
+            ));
+        }
+    }
+    if let Some(fill) = fill_ratio(status) {
+        if let Some((level, threshold)) =
+            exceeded(fill, thresholds.warning_fill, thresholds.critical_fill)
+        {
+            state = state.max(level);
+            problems.push(format!(
+                "{}/{} players online exceeds {}%",
+                status.players_online,
+                status.players_max,
+                round(threshold * 100.0)
+            ));
+        }
+    }
+    if let Some(version) = &thresholds.version {
+        if !version.is_match(&status.version) {
+            state = state.max(State::Warning);
+            problems.push(format!(
+                "version {} does not match {}",
+                status.version, version
+            ));
+        }
+    }
+    (state, problems)
+}
+
+/// 与插件规范一致，超过严重阈值时为 CRITICAL，否则超过警告阈值时为 WARNING，同时返回对应的阈值
+fn exceeded(value: f64, warning: Option<f64>, critical: Option<f64>) -> Option<(State, f64)> {
+    match (warning, critical) {
+        (_, Some(critical)) if value > critical => Some((State::Critical, critical)),
+        (Some(warning), _) if value > warning => Some((State::Warning, warning)),
+        _ => None,
+    }
+}
+
+fn summary(status: &ServerStatus) -> String {
+    let mut summary = format!(
+        "{}, {}/{} players online",
+        status.version, status.players_online, status.players_max
+    );
+    if let Some(latency) = status.latency {
+        summary.push_str(&format!(", latency {} ms", latency.as_millis()));
+    }
+    summary
+}
+
+/// 格式为 `标签=值[单位];警告;严重;最小值;最大值`
+fn perfdata(status: &ServerStatus, thresholds: &Thresholds) -> String {
+    let threshold = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let mut perfdata = vec![format!(
+        "players={};;;0;{}",
+        status.players_online, status.players_max
+    )];
+    if let Some(fill) = fill_ratio(status) {
+        perfdata.push(format!(
+            "fill={}%;{};{};0;100",
+            round(fill * 100.0),
+            threshold(thresholds.warning_fill.map(|fill| round(fill * 100.0))),
+            threshold(thresholds.critical_fill.map(|fill| round(fill * 100.0)))
+        ));
+    }
+    if let Some(latency) = status.latency {
+        let millis = |duration: Duration| round(duration.as_secs_f64() * 1000.0);
+        perfdata.push(format!(
+            "latency={}ms;{};{};0",
+            millis(latency),
+            threshold(thresholds.warning_latency.map(millis)),
+            threshold(thresholds.critical_latency.map(millis))
+        ));
+    }
+    perfdata.join(" ")
+}
+
+/// 人数上限为 0 时没有意义
+fn fill_ratio(status: &ServerStatus) -> Option<f64> {
+    (status.players_max > 0).then(|| status.players_online as f64 / status.players_max as f64)
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use motd::Edition;
+
+    /// 延迟 100 ms、8/10 人在线的服务器
+    fn status() -> ServerStatus {
+        ServerStatus {
+            edition: Edition::Java,
+            version: "Paper 1.20.4".to_string(),
+            protocol: 765,
+            motd: String::new(),
+            players_online: 8,
+            players_max: 10,
+            players: Vec::new(),
+            map: None,
+            game_mode: None,
+            favicon: None,
+            address: "127.0.0.1:25565".parse().unwrap(),
+            srv: None,
+            latency: Some(Duration::from_millis(100)),
+            raw: None,
+            full_stat: None,
+        }
+    }
+
+    fn latency(warning: u64, critical: u64) -> Thresholds {
+        Thresholds {
+            warning_latency: Some(Duration::from_millis(warning)),
+            critical_latency: Some(Duration::from_millis(critical)),
+            ..Thresholds::default()
+        }
+    }
+
+    fn fill(warning: f64, critical: f64) -> Thresholds {
+        Thresholds {
+            warning_fill: Some(warning),
+            critical_fill: Some(critical),
+            ..Thresholds::default()
+        }
+    }
+
+    #[test]
+    fn no_thresholds_is_ok() {
+        let (state, problems) = evaluate(&status(), &Thresholds::default());
+        assert_eq!(state, State::Ok);
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn latency_equal_to_threshold_is_ok() {
+        assert_eq!(evaluate(&status(), &latency(100, 200)).0, State::Ok);
+        assert_eq!(evaluate(&status(), &latency(50, 100)).0, State::Warning);
+    }
+
+    #[test]
+    fn latency_above_threshold() {
+        assert_eq!(evaluate(&status(), &latency(99, 200)).0, State::Warning);
+        assert_eq!(evaluate(&status(), &latency(50, 99)).0, State::Critical);
+    }
+
+    #[test]
+    fn fill_equal_to_threshold_is_ok() {
+        assert_eq!(evaluate(&status(), &fill(0.8, 0.9)).0, State::Ok);
+        assert_eq!(evaluate(&status(), &fill(0.5, 0.8)).0, State::Warning);
+    }
+
+    #[test]
+    fn fill_above_threshold() {
+        assert_eq!(evaluate(&status(), &fill(0.75, 0.9)).0, State::Warning);
+        let (state, problems) = evaluate(&status(), &fill(0.5, 0.75));
+        assert_eq!(state, State::Critical);
+        assert_eq!(problems, ["8/10 players online exceeds 75%"]);
+    }
+
+    #[test]
+    fn version_mismatch_is_warning() {
+        let thresholds = Thresholds {
+            version: Some(Regex::new("^Paper 1\\.21").unwrap()),
+            ..Thresholds::default()
+        };
+        assert_eq!(evaluate(&status(), &thresholds).0, State::Warning);
+    }
+}
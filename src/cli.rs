@@ -1,54 +1,816 @@
+use crate::{
+    check::Thresholds,
+    color::{set_color_depth, ColorDepth},
+    config::{self, config_error, config_path, Config},
+    exporter,
+    graphics::ImageProtocol,
+};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
 use colored::Colorize;
+
+use motd::{
+    i18n::{set_language, Language},
+    tr, Edition, QueryOptions,
+};
+use regex::Regex;
 use unicode_width::UnicodeWidthStr;
 
-use std::{env::args, ffi::OsStr, path::Path, process::exit};
+use std::{
+    env::args,
+    ffi::OsStr,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    path::{Path, PathBuf},
+    process::exit,
+    time::Duration,
+};
+
+/// 第一个参数为这些值时进入对应的模式
+const SUBCOMMANDS: &[&str] = &["watch", "exporter", "serve", "check", "record", "history"];
+
+pub enum Mode {
+    Single {
+        addr: String,
+        port: Option<u16>,
+    },
+    Batch {
+        source: BatchSource,
+        parallel: usize,
+    },
+    Watch {
+        addr: String,
+        port: Option<u16>,
+        interval: Duration,
+    },
+    Check {
+        addr: String,
+        port: Option<u16>,
+        thresholds: Thresholds,
+    },
+    Record {
+        addr: String,
+        port: Option<u16>,
+        store: (PathBuf, StoreFormat),
+        /// 为 `None` 时只记录一次
+        interval: Option<Duration>,
+    },
+    History {
+        /// 为 `None` 时汇总所有服务器
+        target: Option<(String, Option<u16>)>,
+        store: (PathBuf, StoreFormat),
+        since: Option<DateTime<Local>>,
+        until: Option<DateTime<Local>>,
+    },
+    Exporter {
+        source: BatchSource,
+        listen: String,
+        /// 为 `None` 时每次抓取都重新查询
+        interval: Option<Duration>,
+        parallel: usize,
+    },
+    Serve {
+        listen: String,
+        /// 查询结果的缓存时间，为 0 时不缓存
+        ttl: Duration,
+        /// 同时进行的上游查询数上限
+        parallel: usize,
+    },
+}
+
+pub enum BatchSource {
+    /// `--batch` 指定的文件，为 `-` 时读取标准输入
+    File(String),
+    /// 命令行中的多个地址，或未指定地址时配置文件中的收藏
+    List(Vec<String>),
+}
+
+/// `--export-motd` 的导出格式，由文件扩展名决定
+#[derive(Clone, Copy)]
+pub enum MotdFormat {
+    Html,
+    Svg,
+}
+
+/// 历史记录的存储格式，由文件扩展名决定
+#[derive(Clone, Copy)]
+pub enum StoreFormat {
+    Csv,
+    Sqlite,
+}
+
+pub struct Args {
+    pub mode: Mode,
+    pub json: bool,
+    /// 等待两个版本的查询都结束后一并输出
+    pub both: bool,
+    /// 图标的保存路径
+    pub save_icon: Option<PathBuf>,
+    /// 保存前将图标缩放到的边长
+    pub icon_size: Option<u32>,
+    /// Motd 的导出路径与格式
+    pub export_motd: Option<(PathBuf, MotdFormat)>,
+    /// 服务器列表条目图片的保存路径
+    pub render_png: Option<PathBuf>,
+    /// 显示图标使用的图像协议
+    pub image_protocol: ImageProtocol,
+    /// 各模式共用的查询选项，端口由具体模式决定
+    pub options: QueryOptions,
+    pub config: Config,
+}
 
-pub fn parse() -> (String, Option<u16>) {
+pub fn parse() -> Args {
     let args: Vec<String> = args().skip(1).collect();
-    match args.len() {
-        0 => {
-            echo_help();
-            exit(0);
-        }
-        1 => {
-            let arg = &args[0];
-
-            if arg.contains(":") {
-                let ip_port: Vec<&str> = arg.split(":").collect();
-                let ip = ip_port[0].to_string();
-                let port = match ip_port[1].parse::<u16>() {
-                    Ok(port) => Some(port),
+    // 先确定语言，解析其余参数时的提示才能使用对应的语言。check 模式的输出供监控系统解析，固定为英文
+    set_language(if check_mode() {
+        Language::EnUs
+    } else {
+        Language::detect()
+    });
+    let config = config::load();
+    if !check_mode() {
+        if let Some(lang) = &config.lang {
+            match Language::parse(lang) {
+                Some(language) => set_language(language),
+                None => config_error(tr!("配置项 lang 的可选值为 zh-CN、en-US")),
+            }
+        }
+        if let Some(index) = args.iter().position(|arg| arg.starts_with("--lang")) {
+            match args[index].strip_prefix("--lang=") {
+                Some(value) => set_lang(&args, index, value),
+                None if args[index] == "--lang" => {
+                    let mut value_index = index;
+                    let value = option_value(&args, &mut value_index);
+                    set_lang(&args, value_index, value);
+                }
+                None => {}
+            }
+        }
+    }
+    if args.is_empty() && config.favorites.is_empty() {
+        echo_help();
+        exit(0);
+    }
+
+    let mut json = false;
+    let mut options = QueryOptions::default();
+    if let Some(timeout) = config.timeout {
+        if !(timeout > 0.0 && timeout.is_finite()) {
+            config_error(tr!("配置项 timeout 应为正数"));
+        }
+        options.timeout = Duration::from_secs_f64(timeout);
+    }
+    let mut both = false;
+    let mut save_icon: Option<PathBuf> = None;
+    let mut icon_size: Option<u32> = None;
+    let mut export_motd: Option<(PathBuf, MotdFormat)> = None;
+    let mut render_png: Option<PathBuf> = None;
+    let mut image_protocol: Option<ImageProtocol> = None;
+    let mut color: Option<(usize, String)> = None;
+    let mut batch: Option<BatchSource> = None;
+    let mut parallel: usize = 16;
+    let mut interval: Option<u64> = None;
+    let mut listen: Option<String> = None;
+    let mut cache_ttl: u64 = 30;
+    let mut thresholds = Thresholds::default();
+    let mut store: Option<(PathBuf, StoreFormat)> = None;
+    let mut since: Option<DateTime<Local>> = None;
+    let mut until: Option<DateTime<Local>> = None;
+    let subcommand = args
+        .first()
+        .map(String::as_str)
+        .filter(|arg| SUBCOMMANDS.contains(arg));
+    let watch = subcommand == Some("watch");
+    let exporter = subcommand == Some("exporter");
+    if exporter && config.timeout.is_none() {
+        options.timeout = exporter::DEFAULT_TIMEOUT;
+    }
+    let serve = subcommand == Some("serve");
+    let check = subcommand == Some("check");
+    let record = subcommand == Some("record");
+    let history = subcommand == Some("history");
+    let mut positional: Vec<usize> = Vec::new();
+    let mut i = if subcommand.is_some() { 1 } else { 0 };
+    while i < args.len() {
+        match args[i].as_str() {
+            "-h" | "--help" => {
+                echo_help();
+                exit(0);
+            }
+            "--json" => json = true,
+            "--query" => options.full_stat = true,
+            "--java" | "--bedrock" | "--both" => {
+                if both || options.edition.is_some() {
+                    input_error(args, i + 1, tr!("--java、--bedrock 与 --both 只能指定一个"));
+                    exit(usage_exit_code());
+                }
+                match args[i].as_str() {
+                    "--java" => options.edition = Some(Edition::Java),
+                    "--bedrock" => options.edition = Some(Edition::Bedrock),
+                    _ => both = true,
+                }
+            }
+            "--save-icon" => save_icon = Some(PathBuf::from(option_value(&args, &mut i))),
+            "--icon-size" => {
+                icon_size = match option_value(&args, &mut i).parse::<u32>() {
+                    Ok(size) if (1..=4096).contains(&size) => Some(size),
+                    _ => {
+                        input_error(args, i + 1, tr!("图标边长应为 1 - 4096 之间的整数"));
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            "--export-motd" => {
+                let path = PathBuf::from(option_value(&args, &mut i));
+                let extension = path
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .map(str::to_ascii_lowercase);
+                let format = match extension.as_deref() {
+                    Some("html" | "htm") => MotdFormat::Html,
+                    Some("svg") => MotdFormat::Svg,
+                    _ => {
+                        input_error(args, i + 1, tr!("导出文件的扩展名应为 .html 或 .svg"));
+                        exit(usage_exit_code());
+                    }
+                };
+                export_motd = Some((path, format));
+            }
+            "--render-png" => render_png = Some(PathBuf::from(option_value(&args, &mut i))),
+            "--image-protocol" => {
+                image_protocol = match option_value(&args, &mut i) {
+                    "auto" => None,
+                    "kitty" => Some(ImageProtocol::Kitty),
+                    "iterm2" => Some(ImageProtocol::ITerm2),
+                    "sixel" => Some(ImageProtocol::Sixel),
+                    "blocks" => Some(ImageProtocol::HalfBlocks),
+                    _ => {
+                        input_error(
+                            args,
+                            i + 1,
+                            tr!("可选值为 auto、kitty、iterm2、sixel、blocks"),
+                        );
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            "--color" => color = Some((i + 1, option_value(&args, &mut i).to_string())),
+            arg if arg.starts_with("--color=") => {
+                color = Some((i, arg["--color=".len()..].to_string()))
+            }
+            // 语言已在解析前设置
+            "--lang" => i += 1,
+            arg if arg.starts_with("--lang=") => {}
+            "--batch" => batch = Some(BatchSource::File(option_value(&args, &mut i).to_string())),
+            "--parallel" => {
+                parallel = match option_value(&args, &mut i).parse::<usize>() {
+                    Ok(parallel) if parallel > 0 => parallel,
+                    _ => {
+                        input_error(args, i + 1, tr!("并发数应为正整数"));
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            "--timeout" => {
+                options.timeout = match option_value(&args, &mut i).parse::<f64>() {
+                    Ok(timeout) if timeout > 0.0 && timeout.is_finite() => {
+                        Duration::from_secs_f64(timeout)
+                    }
+                    _ => {
+                        input_error(args, i + 1, tr!("超时时间应为正数"));
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            "--dns" => {
+                let value = option_value(&args, &mut i);
+                options.nameserver = match value.parse::<IpAddr>() {
+                    Ok(ip) => Some(SocketAddr::new(ip, 53)),
+                    Err(_) => match value.parse::<SocketAddr>() {
+                        Ok(addr) => Some(addr),
+                        Err(_) => {
+                            input_error(args, i + 1, tr!("DNS 服务器应为 IP 地址"));
+                            exit(usage_exit_code());
+                        }
+                    },
+                }
+            }
+            "--interval" => {
+                interval = match option_value(&args, &mut i).parse::<u64>() {
+                    Ok(interval) if interval > 0 => Some(interval),
+                    _ => {
+                        input_error(args, i + 1, tr!("刷新间隔应为正整数"));
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            "--listen" => {
+                if !exporter && !serve {
+                    input_error(args, i + 1, tr!("--listen 只能用于 exporter 与 serve 模式"));
+                    exit(usage_exit_code());
+                }
+                listen = Some(option_value(&args, &mut i).to_string());
+            }
+            "--cache-ttl" => {
+                if !serve {
+                    input_error(args, i + 1, tr!("--cache-ttl 只能用于 serve 模式"));
+                    exit(usage_exit_code());
+                }
+                cache_ttl = match option_value(&args, &mut i).parse::<u64>() {
+                    Ok(ttl) => ttl,
+                    Err(_) => {
+                        input_error(args, i + 1, tr!("缓存时间应为非负整数"));
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            "--store" => {
+                if !record && !history {
+                    input_error(args, i + 1, tr!("--store 只能用于 record 与 history 模式"));
+                    exit(usage_exit_code());
+                }
+                let path = PathBuf::from(option_value(&args, &mut i));
+                let extension = path
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .map(str::to_ascii_lowercase);
+                let format = match extension.as_deref() {
+                    Some("csv") => StoreFormat::Csv,
+                    Some("db" | "sqlite" | "sqlite3") => StoreFormat::Sqlite,
+                    _ => {
+                        input_error(
+                            args,
+                            i + 1,
+                            tr!("历史记录文件的扩展名应为 .csv、.db 或 .sqlite"),
+                        );
+                        exit(usage_exit_code());
+                    }
+                };
+                store = Some((path, format));
+            }
+            "--since" | "--until" => {
+                if !history {
+                    input_error(args, i + 1, tr!("该选项只能用于 history 模式"));
+                    exit(usage_exit_code());
+                }
+                let time = match parse_time(option_value(&args, &mut i)) {
+                    Some(time) => time,
+                    None => {
+                        input_error(
+                            args,
+                            i + 1,
+                            tr!("时间应为 7d、12h 等时长，或 2024-01-01 [12:00] 形式的日期"),
+                        );
+                        exit(usage_exit_code());
+                    }
+                };
+                if args[i - 1] == "--since" {
+                    since = Some(time);
+                } else {
+                    until = Some(time);
+                }
+            }
+            "--warning-latency" | "--critical-latency" | "--warning-fill" | "--critical-fill"
+            | "--expect-version"
+                if !check =>
+            {
+                input_error(args, i + 1, tr!("该选项只能用于 check 模式"));
+                exit(usage_exit_code());
+            }
+            "--warning-latency" | "--critical-latency" => {
+                let latency = match option_value(&args, &mut i).parse::<u64>() {
+                    Ok(latency) => Duration::from_millis(latency),
                     Err(_) => {
-                        input_error(args, 1, "这是一个不合法的端口号");
-                        exit(1);
+                        input_error(args, i + 1, tr!("延迟阈值应为以毫秒为单位的整数"));
+                        exit(usage_exit_code());
                     }
                 };
-                (ip, port)
-            } else {
-                let ip = arg.to_string();
-                (ip, None)
+                if args[i - 1] == "--warning-latency" {
+                    thresholds.warning_latency = Some(latency);
+                } else {
+                    thresholds.critical_latency = Some(latency);
+                }
+            }
+            "--warning-fill" | "--critical-fill" => {
+                let fill = match parse_ratio(option_value(&args, &mut i)) {
+                    Some(fill) => fill,
+                    None => {
+                        input_error(args, i + 1, tr!("比例应为 0 - 1 之间的小数或百分数"));
+                        exit(usage_exit_code());
+                    }
+                };
+                if args[i - 1] == "--warning-fill" {
+                    thresholds.warning_fill = Some(fill);
+                } else {
+                    thresholds.critical_fill = Some(fill);
+                }
+            }
+            "--expect-version" => {
+                thresholds.version = match Regex::new(option_value(&args, &mut i)) {
+                    Ok(regex) => Some(regex),
+                    Err(_) => {
+                        input_error(args, i + 1, tr!("这是一个不合法的正则表达式"));
+                        exit(usage_exit_code());
+                    }
+                }
+            }
+            arg if arg.starts_with('-') && arg != "-" => {
+                input_error(args, i + 1, tr!("未知的选项"));
+                exit(usage_exit_code());
+            }
+            _ => positional.push(i),
+        }
+        i += 1;
+    }
+
+    if icon_size.is_some() && save_icon.is_none() {
+        usage_error(tr!("--icon-size 需要与 --save-icon 一起使用"));
+    }
+    // 没有指定地址时查询配置文件中收藏的服务器
+    if batch.is_none()
+        && (subcommand.is_none() || exporter)
+        && positional.is_empty()
+        && !config.favorites.is_empty()
+    {
+        batch = Some(BatchSource::List(config.favorites.clone()));
+    }
+    let exceeds = |warning: Option<f64>, critical: Option<f64>| {
+        warning
+            .zip(critical)
+            .is_some_and(|(warning, critical)| warning > critical)
+    };
+    let as_secs = |latency: Option<Duration>| latency.map(|latency| latency.as_secs_f64());
+    if exceeds(
+        as_secs(thresholds.warning_latency),
+        as_secs(thresholds.critical_latency),
+    ) || exceeds(thresholds.warning_fill, thresholds.critical_fill)
+    {
+        usage_error(tr!("警告阈值不能大于严重阈值"));
+    }
+    let exports = save_icon.is_some() || export_motd.is_some() || render_png.is_some();
+    if (both || exports) && (subcommand.is_some() || batch.is_some()) {
+        usage_error(tr!("--both 与导出类选项只能用于查询单个服务器"));
+    }
+
+    let image_protocol = image_protocol.unwrap_or_else(ImageProtocol::detect);
+    match (color, &config.color) {
+        (Some((index, value)), _) => {
+            if !set_color(&value) {
+                input_error(
+                    args,
+                    index + 1,
+                    tr!("可选值为 auto、always、never、256、16"),
+                );
+                exit(usage_exit_code());
+            }
+        }
+        (None, Some(value)) => {
+            if !set_color(value) {
+                config_error(tr!("配置项 color 的可选值为 auto、always、never、256、16"));
+            }
+        }
+        (None, None) => {
+            set_color("auto");
+        }
+    }
+
+    if (record || history) && batch.is_some() {
+        usage_error(tr!("record 与 history 模式不能使用 --batch"));
+    }
+    let store = match store {
+        Some(store) => Some(store),
+        None if record || history => {
+            usage_error(tr!("请使用 --store 指定历史记录文件"));
+        }
+        None => None,
+    };
+    // 不指定地址时汇总文件中的所有服务器
+    if history && positional.is_empty() {
+        return Args {
+            mode: Mode::History {
+                target: None,
+                store: store.unwrap(),
+                since,
+                until,
+            },
+            json,
+            both,
+            save_icon,
+            icon_size,
+            export_motd,
+            render_png,
+            image_protocol,
+            options,
+            config,
+        };
+    }
+
+    if serve {
+        // 地址由请求路径给出
+        if let Some(&index) = positional.first() {
+            input_error(args, index + 1, tr!("serve 模式不需要指定地址"));
+            exit(usage_exit_code());
+        }
+        if batch.is_some() {
+            usage_error(tr!("serve 模式不能使用 --batch"));
+        }
+        return Args {
+            mode: Mode::Serve {
+                listen: listen.unwrap_or_else(|| "127.0.0.1:8080".to_string()),
+                ttl: Duration::from_secs(cache_ttl),
+                parallel,
+            },
+            json,
+            both,
+            save_icon,
+            icon_size,
+            export_motd,
+            render_png,
+            image_protocol,
+            options,
+            config,
+        };
+    }
+
+    if exporter {
+        // exporter 可以监控多个地址，也可以使用 --batch 或收藏
+        let source = match batch {
+            Some(source @ BatchSource::File(_)) if positional.is_empty() => source,
+            Some(BatchSource::File(_)) => {
+                input_error(args, positional[0] + 1, tr!("批量模式下不能再指定地址"));
+                exit(usage_exit_code());
+            }
+            _ if !positional.is_empty() => {
+                BatchSource::List(positional.iter().map(|&i| args[i].clone()).collect())
+            }
+            Some(source) => source,
+            None => {
+                usage_error(tr!("请输入服务器地址"));
             }
+        };
+        return Args {
+            mode: Mode::Exporter {
+                source,
+                listen: listen.unwrap_or_else(|| "0.0.0.0:9565".to_string()),
+                interval: interval.map(Duration::from_secs),
+                parallel,
+            },
+            json,
+            both,
+            save_icon,
+            icon_size,
+            export_motd,
+            render_png,
+            image_protocol,
+            options,
+            config,
+        };
+    }
+
+    if let Some(source) = batch {
+        if !positional.is_empty() {
+            input_error(args, positional[0] + 1, tr!("批量模式下不能再指定地址"));
+            exit(usage_exit_code());
         }
-        2 => {
-            let ip = args[0].to_string();
-            let port = match args[1].parse::<u16>() {
+        return Args {
+            mode: Mode::Batch { source, parallel },
+            json,
+            both,
+            save_icon,
+            icon_size,
+            export_motd,
+            render_png,
+            image_protocol,
+            options,
+            config,
+        };
+    }
+
+    let (addr, port) = match positional[..] {
+        [] => {
+            usage_error(tr!("请输入服务器地址"));
+        }
+        [i] => resolve_address(&args, i, &config),
+        [i, j] => {
+            let port = match args[j].parse::<u16>() {
                 Ok(port) => Some(port),
                 Err(_) => {
-                    input_error(args, 2, "这是一个不合法的端口号");
-                    exit(1);
+                    input_error(args, j + 1, tr!("这是一个不合法的端口号"));
+                    exit(usage_exit_code());
                 }
             };
-            (ip, port)
+            // 别名中的端口被单独指定的端口覆盖
+            let addr = match config.aliases.get(&args[i]) {
+                Some(_) => resolve_address(&args, i, &config).0,
+                None => args[i].to_string(),
+            };
+            (addr, port)
         }
         _ => {
-            println!("{}", "您的输入参数过多".bright_red().bold());
-            exit(1);
+            usage_error(tr!("您的输入参数过多"));
+        }
+    };
+
+    let mode = if watch {
+        Mode::Watch {
+            addr,
+            port,
+            interval: Duration::from_secs(interval.unwrap_or(5)),
+        }
+    } else if check {
+        Mode::Check {
+            addr,
+            port,
+            thresholds,
+        }
+    } else if record {
+        Mode::Record {
+            addr,
+            port,
+            store: store.unwrap(),
+            interval: interval.map(Duration::from_secs),
+        }
+    } else if history {
+        Mode::History {
+            target: Some((addr, port)),
+            store: store.unwrap(),
+            since,
+            until,
+        }
+    } else {
+        Mode::Single { addr, port }
+    };
+    Args {
+        mode,
+        json,
+        both,
+        save_icon,
+        icon_size,
+        export_motd,
+        render_png,
+        image_protocol,
+        options,
+        config,
+    }
+}
+
+/// 解析第 `index` 个参数中的地址，是别名时使用配置文件中的地址
+fn resolve_address(args: &[String], index: usize, config: &Config) -> (String, Option<u16>) {
+    if let Some(alias) = config.aliases.get(&args[index]) {
+        return split_address(alias)
+            .unwrap_or_else(|| config_error(&tr!("别名 {} 的地址不合法", args[index])));
+    }
+    match split_address(&args[index]) {
+        Some(addr) => addr,
+        None if args[index].starts_with('[') => {
+            input_error(args.to_vec(), index + 1, tr!("IPv6 地址应写为 [地址]:端口"));
+            exit(usage_exit_code());
+        }
+        None => {
+            input_error(args.to_vec(), index + 1, tr!("这是一个不合法的端口号"));
+            exit(usage_exit_code());
+        }
+    }
+}
+
+/// 将 `<地址>`、`<地址>:<端口>` 或 `[<IPv6 地址>]:<端口>` 拆分，端口不合法时返回 `None`
+pub fn split_address(arg: &str) -> Option<(String, Option<u16>)> {
+    if let Some(rest) = arg.strip_prefix('[') {
+        let (ip, port) = rest.split_once(']')?;
+        return match port {
+            "" => Some((ip.to_string(), None)),
+            port => port
+                .strip_prefix(':')?
+                .parse::<u16>()
+                .ok()
+                .map(|port| (ip.to_string(), Some(port))),
+        };
+    }
+    // 不带方括号的 IPv6 地址中不能指定端口
+    if arg.parse::<Ipv6Addr>().is_ok() {
+        return Some((arg.to_string(), None));
+    }
+    match arg.split_once(':') {
+        Some((ip, port)) => port
+            .parse::<u16>()
+            .ok()
+            .map(|port| (ip.to_string(), Some(port))),
+        None => Some((arg.to_string(), None)),
+    }
+}
+
+/// 第一个参数为 `check` 时，所有输出都需遵循监控插件的规范
+pub fn check_mode() -> bool {
+    args().nth(1).is_some_and(|arg| arg == "check")
+}
+
+/// 参数或配置有误时的退出码，check 模式下按插件规范为 3 (UNKNOWN)
+pub fn usage_exit_code() -> i32 {
+    if check_mode() {
+        3
+    } else {
+        1
+    }
+}
+
+/// 输出参数错误并退出，check 模式下输出单行的 UNKNOWN 状态
+fn usage_error(message: &str) -> ! {
+    if check_mode() {
+        println!("MOTD UNKNOWN - {}", message);
+    } else {
+        println!("{}", message.bright_red().bold());
+    }
+    exit(usage_exit_code());
+}
+
+/// 解析 `30m`、`12h`、`7d`、`2w` 形式的时长 (从现在往前推算)，或本地时间的日期
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    let now = Local::now();
+    if let Some(unit) = value.chars().last().filter(char::is_ascii_alphabetic) {
+        let amount = value[..value.len() - 1].parse::<i64>().ok()?;
+        let duration = match unit {
+            'm' => TimeDelta::try_minutes(amount)?,
+            'h' => TimeDelta::try_hours(amount)?,
+            'd' => TimeDelta::try_days(amount)?,
+            'w' => TimeDelta::try_weeks(amount)?,
+            _ => return None,
+        };
+        return now.checked_sub_signed(duration);
+    }
+    let time = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .ok()?;
+    time.and_local_timezone(Local).earliest()
+}
+
+/// 解析 `0.9` 或 `90%` 形式的比例
+fn parse_ratio(value: &str) -> Option<f64> {
+    let ratio = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+        None => value.parse::<f64>().ok()?,
+    };
+    (0.0..=1.0).contains(&ratio).then_some(ratio)
+}
+
+/// 处理 `--color` 或配置文件中的颜色模式，取值不合法时返回 `false`
+fn set_color(value: &str) -> bool {
+    let depth = match value {
+        "auto" => {
+            // TERM=dumb 的终端不支持任何转义序列
+            if std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+                colored::control::set_override(false);
+            }
+            ColorDepth::detect()
+        }
+        "always" => {
+            colored::control::set_override(true);
+            ColorDepth::detect()
+        }
+        "never" => {
+            colored::control::set_override(false);
+            ColorDepth::detect()
+        }
+        "256" => {
+            colored::control::set_override(true);
+            ColorDepth::Ansi256
+        }
+        "16" => {
+            colored::control::set_override(true);
+            ColorDepth::Ansi16
+        }
+        _ => return false,
+    };
+    set_color_depth(depth);
+    true
+}
+
+/// 处理 `--lang`，`index` 为该值在参数中的位置
+fn set_lang(args: &[String], index: usize, value: &str) {
+    match Language::parse(value) {
+        Some(language) => set_language(language),
+        None => {
+            input_error(args.to_vec(), index + 1, tr!("可选值为 zh-CN、en-US"));
+            exit(usage_exit_code());
         }
     }
 }
 
+fn option_value<'a>(args: &'a [String], i: &mut usize) -> &'a str {
+    if *i + 1 >= args.len() {
+        input_error(args.to_vec(), *i + 1, tr!("该选项需要一个值"));
+        exit(usage_exit_code());
+    }
+    *i += 1;
+    &args[*i]
+}
+
 fn input_error(args: Vec<String>, error_arg: usize, error_msg: &str) {
-    println!("{}", "您的输入有误".bright_red().bold());
+    if check_mode() {
+        println!("MOTD UNKNOWN - {}: {}", args[error_arg - 1], error_msg);
+        return;
+    }
+    println!("{}", tr!("您的输入有误").bright_red().bold());
     println!(
         "{} {} {} {} {}",
         "-->".bright_cyan().bold(),
@@ -73,7 +835,7 @@ fn input_error(args: Vec<String>, error_arg: usize, error_msg: &str) {
     println!(
         " {}  {}",
         "=".bright_cyan().bold(),
-        format!(
+        tr!(
             "使用 {} -h 查看帮助",
             get_current_exe_file_name().bright_yellow()
         )
@@ -85,25 +847,155 @@ fn echo_help() {
     println!("Motd {}", env!("CARGO_PKG_VERSION").bright_green().bold());
     println!(
         "{}\n",
-        "跨平台的 Minecraft 服务器 Motd 测试工具"
+        tr!("跨平台的 Minecraft 服务器 Motd 测试工具")
             .bright_cyan()
             .bold()
     );
-    println!(
-        "   食用方法 | {} <IP> <端口>",
-        &current_exe_file_name.bright_yellow()
-    );
-    println!(
-        "            | {} <IP:端口>",
-        &current_exe_file_name.bright_yellow()
-    );
-    println!("            |");
-    println!(
-        "Github Repo | {}",
-        "https://github.com/cnlancehu/motd".bright_cyan()
+    let usages = [
+        tr!("<IP> <端口>"),
+        tr!("<IP:端口>"),
+        tr!("--batch <文件>"),
+        tr!("watch <IP:端口>"),
+        tr!("exporter <IP:端口>..."),
+        "serve",
+        tr!("check <IP:端口>"),
+        tr!("record <IP:端口> --store <文件>"),
+        tr!("history [IP:端口] --store <文件>"),
+    ];
+    for (index, usage) in usages.into_iter().enumerate() {
+        help_line(
+            if index == 0 { tr!("食用方法") } else { "" },
+            format!("{} {}", current_exe_file_name.bright_yellow(), usage),
+        );
+    }
+    help_line("", String::new());
+    let options = [
+        ("--json", tr!("以 JSON 格式输出")),
+        ("--query", tr!("通过 Query 协议获取完整玩家列表和插件")),
+        (tr!("--timeout <秒>"), tr!("查询的超时时间，默认为 20 秒，exporter 默认为 8 秒")),
+        (
+            "--dns <IP>",
+            tr!("查询 SRV 记录的 DNS 服务器，默认读取 /etc/resolv.conf，Windows 上需指定才会查询 SRV"),
+        ),
+        ("--java", tr!("只查询 Java 版")),
+        ("--bedrock", tr!("只查询基岩版")),
+        ("--both", tr!("同时显示两个版本的结果")),
+        (
+            tr!("--save-icon <路径>"),
+            tr!("将服务器图标保存为 PNG 文件"),
+        ),
+        ("--icon-size <n>", tr!("保存前将图标缩放为 n×n 像素")),
+        (
+            tr!("--export-motd <路径>"),
+            tr!("将 Motd 导出为 HTML 或 SVG 文件"),
+        ),
+        (
+            tr!("--render-png <路径>"),
+            tr!("仿照游戏内的服务器列表绘制为 PNG 图片"),
+        ),
+        (
+            "--image-protocol <p>",
+            tr!("显示图标的方式: auto、kitty、iterm2、sixel、blocks"),
+        ),
+        (
+            tr!("--color <模式>"),
+            tr!("颜色: auto、always、never、256、16"),
+        ),
+        (
+            tr!("--lang <语言>"),
+            tr!("界面语言: zh-CN、en-US，默认跟随系统"),
+        ),
+        (
+            tr!("--batch <文件>"),
+            tr!("从文件批量查询，为 - 时读取标准输入"),
+        ),
+        (
+            "--parallel <n>",
+            tr!("批量查询与 serve 的并发数，默认为 16"),
+        ),
+        (
+            tr!("--interval <秒>"),
+            tr!("刷新间隔: watch 默认 5 秒，exporter 默认随抓取查询，record 默认只记录一次"),
+        ),
+        (
+            tr!("--listen <地址>"),
+            tr!("exporter 与 serve 监听的地址，默认为 0.0.0.0:9565 与 127.0.0.1:8080"),
+        ),
+        (
+            tr!("--cache-ttl <秒>"),
+            tr!("serve 缓存查询结果的时间，默认为 30 秒"),
+        ),
+        (
+            tr!("--store <文件>"),
+            tr!("历史记录文件: .csv 或 .db (SQLite)"),
+        ),
+        (
+            tr!("--since <时间>"),
+            tr!("history 的起始时间，如 7d、2024-01-01"),
+        ),
+        (tr!("--until <时间>"), tr!("history 的结束时间，默认为现在")),
+        (
+            tr!("--warning-latency <毫秒>"),
+            tr!("check 的延迟阈值，超过时为 WARNING"),
+        ),
+        (
+            tr!("--critical-latency <毫秒>"),
+            tr!("check 的延迟阈值，超过时为 CRITICAL"),
+        ),
+        (
+            tr!("--warning-fill <比例>"),
+            tr!("check 的人数占比阈值，如 0.9 或 90%"),
+        ),
+        (
+            tr!("--critical-fill <比例>"),
+            tr!("check 的人数占比阈值，超过时为 CRITICAL"),
+        ),
+        (
+            tr!("--expect-version <正则>"),
+            tr!("check 时版本不匹配则为 WARNING"),
+        ),
+    ];
+    // 说明一列对齐到最长的选项名之后两格
+    let column = options
+        .iter()
+        .map(|(option, _)| UnicodeWidthStr::width(*option))
+        .max()
+        .unwrap_or(0)
+        + 2;
+    for (index, (option, description)) in options.into_iter().enumerate() {
+        let padding = column - UnicodeWidthStr::width(option);
+        help_line(
+            if index == 0 { tr!("选项") } else { "" },
+            format!(
+                "{}{}{}",
+                option.bright_cyan(),
+                " ".repeat(padding),
+                description
+            ),
+        );
+    }
+    help_line("", String::new());
+    if let Some(path) = config_path() {
+        help_line(tr!("配置文件"), path.display().to_string());
+    }
+    help_line(
+        "Github Repo",
+        "https://github.com/cnlancehu/motd"
+            .bright_cyan()
+            .to_string(),
     );
 }
 
+/// 帮助中的一行，标题右对齐到 11 列
+fn help_line(title: &str, content: String) {
+    let padding = " ".repeat(11usize.saturating_sub(UnicodeWidthStr::width(title)));
+    if content.is_empty() {
+        println!("{}{} |", padding, title);
+    } else {
+        println!("{}{} | {}", padding, title, content);
+    }
+}
+
 fn get_current_exe_file_name() -> String {
     args()
         .next()
@@ -115,3 +1007,42 @@ fn get_current_exe_file_name() -> String {
         })
         .unwrap_or("motd".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(addr: &str, port: Option<u16>) -> Option<(String, Option<u16>)> {
+        Some((addr.to_string(), port))
+    }
+
+    #[test]
+    fn split_host_and_port() {
+        assert_eq!(
+            split_address("mc.example.com"),
+            address("mc.example.com", None)
+        );
+        assert_eq!(
+            split_address("mc.example.com:25566"),
+            address("mc.example.com", Some(25566))
+        );
+    }
+
+    #[test]
+    fn split_ipv6() {
+        assert_eq!(split_address("[::1]:19132"), address("::1", Some(19132)));
+        assert_eq!(split_address("[2001:db8::1]"), address("2001:db8::1", None));
+        // 不带方括号时整体视为地址
+        assert_eq!(split_address("2001:db8::1"), address("2001:db8::1", None));
+    }
+
+    #[test]
+    fn split_invalid_port() {
+        assert_eq!(split_address("mc.example.com:65536"), None);
+        assert_eq!(split_address("mc.example.com:abc"), None);
+        assert_eq!(split_address("mc.example.com:"), None);
+        assert_eq!(split_address("[::1]19132"), None);
+        assert_eq!(split_address("[::1]:x"), None);
+        assert_eq!(split_address("[::1"), None);
+    }
+}
@@ -0,0 +1,151 @@
+use colored::{Color, ColoredString, Colorize};
+
+use std::{
+    env,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+type Rgb = (u8, u8, u8);
+
+/// 终端支持的颜色数量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+static DEPTH: AtomicU8 = AtomicU8::new(ColorDepth::TrueColor as u8);
+
+/// 与原版 Minecraft 一致的 CGA 16 色，顺序与 ANSI 颜色编号相同
+const ANSI16: [(Rgb, Color); 16] = [
+    ((0, 0, 0), Color::Black),
+    ((170, 0, 0), Color::Red),
+    ((0, 170, 0), Color::Green),
+    ((170, 85, 0), Color::Yellow),
+    ((0, 0, 170), Color::Blue),
+    ((170, 0, 170), Color::Magenta),
+    ((0, 170, 170), Color::Cyan),
+    ((170, 170, 170), Color::White),
+    ((85, 85, 85), Color::BrightBlack),
+    ((255, 85, 85), Color::BrightRed),
+    ((85, 255, 85), Color::BrightGreen),
+    ((255, 255, 85), Color::BrightYellow),
+    ((85, 85, 255), Color::BrightBlue),
+    ((255, 85, 255), Color::BrightMagenta),
+    ((85, 255, 255), Color::BrightCyan),
+    ((255, 255, 255), Color::BrightWhite),
+];
+
+/// xterm-256 中 6×6×6 色块每一级的亮度
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// 根据 COLORTERM 与 TERM 判断终端的颜色深度
+    pub fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        match env::var("TERM") {
+            Ok(term) if term.contains("truecolor") || term.contains("direct") => {
+                ColorDepth::TrueColor
+            }
+            Ok(term) if term.contains("256") => ColorDepth::Ansi256,
+            Ok(_) => ColorDepth::Ansi16,
+            // Windows Terminal 支持真彩色，conhost 只能可靠地显示 16 色
+            Err(_) if cfg!(windows) && env::var_os("WT_SESSION").is_some() => ColorDepth::TrueColor,
+            Err(_) if cfg!(windows) => ColorDepth::Ansi16,
+            Err(_) => ColorDepth::TrueColor,
+        }
+    }
+}
+
+pub fn set_color_depth(depth: ColorDepth) {
+    DEPTH.store(depth as u8, Ordering::Relaxed);
+}
+
+fn color_depth() -> ColorDepth {
+    match DEPTH.load(Ordering::Relaxed) {
+        depth if depth == ColorDepth::Ansi256 as u8 => ColorDepth::Ansi256,
+        depth if depth == ColorDepth::Ansi16 as u8 => ColorDepth::Ansi16,
+        _ => ColorDepth::TrueColor,
+    }
+}
+
+/// 按当前的颜色深度为文本设置前景色与背景色，颜色不足时取最接近的颜色
+pub fn paint(text: ColoredString, fg: Option<Rgb>, bg: Option<Rgb>) -> String {
+    match color_depth() {
+        ColorDepth::TrueColor => {
+            let mut text = text;
+            if let Some((r, g, b)) = fg {
+                text = text.truecolor(r, g, b);
+            }
+            if let Some((r, g, b)) = bg {
+                text = text.on_truecolor(r, g, b);
+            }
+            text.to_string()
+        }
+        ColorDepth::Ansi16 => {
+            let mut text = text;
+            if let Some(fg) = fg {
+                text = text.color(ANSI16[nearest_ansi16(fg)].1);
+            }
+            if let Some(bg) = bg {
+                text = text.on_color(ANSI16[nearest_ansi16(bg)].1);
+            }
+            text.to_string()
+        }
+        // colored 不支持 256 色，直接拼接转义序列
+        ColorDepth::Ansi256 => {
+            let mut codes = Vec::new();
+            if let Some(fg) = fg {
+                codes.push(format!("38;5;{}", nearest_ansi256(fg)));
+            }
+            if let Some(bg) = bg {
+                codes.push(format!("48;5;{}", nearest_ansi256(bg)));
+            }
+            if codes.is_empty() || plain_output() {
+                return text.to_string();
+            }
+            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+        }
+    }
+}
+
+/// 设置了 NO_COLOR、输出不是终端或指定了 `--color=never` 时不输出任何转义序列
+pub fn plain_output() -> bool {
+    !colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+fn distance((r1, g1, b1): Rgb, (r2, g2, b2): Rgb) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_ansi16(rgb: Rgb) -> usize {
+    (0..ANSI16.len())
+        .min_by_key(|&index| distance(rgb, ANSI16[index].0))
+        .unwrap()
+}
+
+/// 在 6×6×6 色块与 24 级灰阶中取最接近的颜色
+fn nearest_ansi256(rgb: Rgb) -> u8 {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&index| (CUBE_LEVELS[index] as i32 - value as i32).abs())
+            .unwrap()
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (16 + r * 36 + g * 6 + b) as u8;
+    let cube_rgb = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + gray_index * 10;
+    if distance(rgb, (gray, gray, gray)) < distance(rgb, cube_rgb) {
+        232 + gray_index
+    } else {
+        cube
+    }
+}
@@ -0,0 +1,79 @@
+use crate::cli::{check_mode, usage_exit_code};
+
+use colored::Colorize;
+use motd::tr;
+use serde::Deserialize;
+
+use std::{collections::BTreeMap, env, fs, io, path::PathBuf, process::exit};
+
+/// 配置文件的内容，所有项都可以省略，命令行参数优先于配置文件
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// 默认的超时时间 (秒)
+    pub timeout: Option<f64>,
+    /// 默认的颜色模式，与 `--color` 的取值相同
+    pub color: Option<String>,
+    /// 默认的界面语言，与 `--lang` 的取值相同
+    pub lang: Option<String>,
+    /// 不带参数运行时查询的服务器，可以是地址或别名
+    pub favorites: Vec<String>,
+    /// 服务器别名，值为 `<地址>` 或 `<地址>:<端口>`
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// 是别名时返回对应的地址，否则原样返回
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(name).map_or(name, String::as_str)
+    }
+}
+
+/// 依次使用 `$XDG_CONFIG_HOME`、`~/.config`，Windows 上使用 `%APPDATA%`
+pub fn config_path() -> Option<PathBuf> {
+    let dir = |name: &str| env::var_os(name).filter(|dir| !dir.is_empty());
+    let base = if cfg!(windows) {
+        dir("APPDATA").map(PathBuf::from)
+    } else {
+        dir("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| dir("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }?;
+    Some(base.join("motd").join("config.toml"))
+}
+
+/// 读取配置文件，文件不存在时使用默认配置，内容有误时直接退出
+pub fn load() -> Config {
+    let Some(path) = config_path() else {
+        return Config::default();
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Config::default(),
+        Err(e) => config_error(&e.to_string()),
+    };
+    toml::from_str(&content).unwrap_or_else(|e| config_error(&e.to_string()))
+}
+
+/// 配置文件有误时输出文件路径与原因并退出
+pub fn config_error(message: &str) -> ! {
+    if check_mode() {
+        println!(
+            "MOTD UNKNOWN - {}: {}",
+            tr!("配置文件有误"),
+            message.trim_end()
+        );
+        exit(usage_exit_code());
+    }
+    println!(
+        "{}\n{} {}\n{}",
+        tr!("配置文件有误").bright_red().bold(),
+        "-->".bright_cyan().bold(),
+        config_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default()
+            .bright_yellow(),
+        message.trim_end().bright_red()
+    );
+    exit(usage_exit_code());
+}
@@ -0,0 +1,67 @@
+use crate::tr;
+
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum QueryError {
+    /// 地址无法解析
+    Resolve(io::Error),
+    /// 连接被拒绝或目标不可达
+    Connect(io::Error),
+    /// 未在限定时间内得到有效响应
+    Timeout,
+    /// 收到了响应，但不是可以识别的 Minecraft 服务器
+    Protocol(String),
+}
+
+impl QueryError {
+    /// 按 io 错误的类型归入连接、超时或协议阶段
+    pub(crate) fn from_io(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::HostUnreachable
+            | io::ErrorKind::NetworkUnreachable
+            | io::ErrorKind::AddrNotAvailable => QueryError::Connect(e),
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => QueryError::Timeout,
+            io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted => {
+                QueryError::Protocol(tr!("服务器在响应完成前断开了连接").to_string())
+            }
+            _ => QueryError::Protocol(e.to_string()),
+        }
+    }
+
+    /// 两种协议都失败时，用于挑选最能说明问题的错误
+    pub(crate) fn rank(&self) -> u8 {
+        match self {
+            QueryError::Resolve(_) => 0,
+            QueryError::Timeout => 1,
+            QueryError::Connect(_) => 2,
+            QueryError::Protocol(_) => 3,
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Resolve(e) => write!(f, "{}", tr!("无法解析地址: {}", e)),
+            QueryError::Connect(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                f.write_str(tr!("连接被拒绝，服务器可能未开启或端口错误"))
+            }
+            QueryError::Connect(e) => write!(f, "{}", tr!("无法建立连接: {}", e)),
+            QueryError::Timeout => f.write_str(tr!("连接超时")),
+            QueryError::Protocol(e) => write!(f, "{}", tr!("无法识别服务器的响应: {}", e)),
+        }
+    }
+}
+
+impl Error for QueryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            QueryError::Resolve(e) | QueryError::Connect(e) => Some(e),
+            QueryError::Timeout | QueryError::Protocol(_) => None,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! 将 Motd 导出为 HTML 或 SVG，便于在网页中按游戏内的样式展示
+
+use crate::text::Span;
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+/// 未指定颜色的文本在多人游戏列表中显示为灰色
+const DEFAULT_COLOR: (u8, u8, u8) = (170, 170, 170);
+const FONT_FAMILY: &str = "Minecraft, Monocraft, monospace";
+const FONT_SIZE: u32 = 16;
+const LINE_HEIGHT: u32 = 20;
+/// 按等宽字体估算的字符宽度
+const CHAR_WIDTH: f32 = FONT_SIZE as f32 * 0.6;
+const PADDING: u32 = 8;
+/// SVG 中乱码效果的候选帧数与每帧时长
+const OBFUSCATED_FRAMES: usize = 8;
+const OBFUSCATED_FRAME_MS: usize = 50;
+const OBFUSCATED_CHARS: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789#$%&?@";
+
+/// 为 `mc-obfuscated` 元素实现乱码效果的脚本，可与 [`to_html`] 的结果一起嵌入网页
+pub const OBFUSCATED_SCRIPT: &str = r#"<script>
+(() => {
+  const chars = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789#$%&?@";
+  const nodes = [...document.querySelectorAll(".mc-obfuscated")];
+  nodes.forEach((node) => (node.dataset.length = [...node.textContent].length));
+  setInterval(() => {
+    for (const node of nodes) {
+      let text = "";
+      for (let i = 0; i < node.dataset.length; i++) {
+        text += chars[Math.floor(Math.random() * chars.length)];
+      }
+      node.textContent = text;
+    }
+  }, 50);
+})();
+</script>"#;
+
+/// 生成由 `<span>` 组成的 HTML 片段，换行转为 `<br>`，乱码文本带有 `mc-obfuscated` 类
+pub fn to_html(spans: &[Span]) -> String {
+    let mut html = format!(
+        "<div class=\"mc-motd\" style=\"font-family: {}; white-space: pre; line-height: {}px\">",
+        FONT_FAMILY, LINE_HEIGHT
+    );
+    for span in spans {
+        for (index, line) in span.text.split('\n').enumerate() {
+            if index > 0 {
+                html.push_str("<br>");
+            }
+            if line.is_empty() {
+                continue;
+            }
+            let style = &span.style;
+            let mut css = format!("color: {}", hex(style.color.unwrap_or(DEFAULT_COLOR)));
+            if style.bold {
+                css.push_str("; font-weight: bold");
+            }
+            if style.italic {
+                css.push_str("; font-style: italic");
+            }
+            if let Some(decoration) = text_decoration(span) {
+                css.push_str("; text-decoration: ");
+                css.push_str(decoration);
+            }
+            let class = if style.obfuscated {
+                " class=\"mc-obfuscated\""
+            } else {
+                ""
+            };
+            html.push_str(&format!(
+                "<span{} style=\"{}\">{}</span>",
+                class,
+                css,
+                escape(line)
+            ));
+        }
+    }
+    html.push_str("</div>");
+    html
+}
+
+/// 包含样式与乱码脚本的完整 HTML 页面
+pub fn html_document(spans: &[Span]) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Motd</title>\n</head>\n<body style=\"background: #1e1e1e; font-size: {}px\">\n{}\n{}\n</body>\n</html>\n",
+        FONT_SIZE,
+        to_html(spans),
+        OBFUSCATED_SCRIPT
+    )
+}
+
+/// 生成独立的 SVG 图像，乱码文本通过 SMIL 动画轮流显示随机字符
+pub fn to_svg(spans: &[Span]) -> String {
+    let lines = split_lines(spans);
+    let columns = lines
+        .iter()
+        .map(|line| {
+            line.iter()
+                .map(|span| span.text.chars().count())
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(0);
+    let width = (columns as f32 * CHAR_WIDTH).ceil() as u32 + PADDING * 2;
+    let height = lines.len() as u32 * LINE_HEIGHT + PADDING * 2;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"#1e1e1e\"/>\n\
+         <g font-family=\"{}\" font-size=\"{}\" xml:space=\"preserve\">\n",
+        FONT_FAMILY,
+        FONT_SIZE,
+        w = width,
+        h = height
+    );
+    for (index, line) in lines.iter().enumerate() {
+        // 文本基线位于行高的底部留出下伸部分的位置
+        let y = PADDING + index as u32 * LINE_HEIGHT + FONT_SIZE;
+        svg.push_str(&format!("<text x=\"{}\" y=\"{}\">", PADDING, y));
+        for span in line {
+            if span.style.obfuscated {
+                push_obfuscated_tspans(&mut svg, span);
+            } else {
+                svg.push_str(&format!(
+                    "<tspan{}>{}</tspan>",
+                    svg_attributes(span),
+                    escape(&span.text)
+                ));
+            }
+        }
+        svg.push_str("</text>\n");
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// 每一帧为一个 tspan，隐藏的 tspan 不占位置，因此轮流切换 display 即可原地替换字符
+fn push_obfuscated_tspans(svg: &mut String, span: &Span) {
+    let length = span.text.chars().count();
+    let duration = OBFUSCATED_FRAMES * OBFUSCATED_FRAME_MS;
+    for frame in 0..OBFUSCATED_FRAMES {
+        let values: Vec<&str> = (0..OBFUSCATED_FRAMES)
+            .map(|other| if other == frame { "inline" } else { "none" })
+            .collect();
+        svg.push_str(&format!(
+            "<tspan{}{}>{}<animate attributeName=\"display\" values=\"{}\" dur=\"{}ms\" calcMode=\"discrete\" repeatCount=\"indefinite\"/></tspan>",
+            svg_attributes(span),
+            if frame == 0 { "" } else { " display=\"none\"" },
+            escape(&random_text(length)),
+            values.join(";"),
+            duration
+        ));
+    }
+}
+
+fn svg_attributes(span: &Span) -> String {
+    let style = &span.style;
+    let mut attributes = format!(" fill=\"{}\"", hex(style.color.unwrap_or(DEFAULT_COLOR)));
+    if style.bold {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if style.italic {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if let Some(decoration) = text_decoration(span) {
+        attributes.push_str(&format!(" text-decoration=\"{}\"", decoration));
+    }
+    attributes
+}
+
+fn text_decoration(span: &Span) -> Option<&'static str> {
+    match (span.style.underlined, span.style.strikethrough) {
+        (true, true) => Some("underline line-through"),
+        (true, false) => Some("underline"),
+        (false, true) => Some("line-through"),
+        (false, false) => None,
+    }
+}
+
+/// 按换行符拆分为多行，每行保留各自的片段
+fn split_lines(spans: &[Span]) -> Vec<Vec<Span>> {
+    let mut lines = vec![Vec::new()];
+    for span in spans {
+        for (index, text) in span.text.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+            }
+            if !text.is_empty() {
+                lines.last_mut().unwrap().push(Span {
+                    text: text.to_string(),
+                    style: span.style,
+                });
+            }
+        }
+    }
+    lines
+}
+
+fn random_text(length: usize) -> String {
+    (0..length)
+        .map(|_| {
+            let random = RandomState::new().build_hasher().finish();
+            OBFUSCATED_CHARS[(random % OBFUSCATED_CHARS.len() as u64) as usize] as char
+        })
+        .collect()
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
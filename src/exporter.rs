@@ -0,0 +1,237 @@
+use crate::{
+    batch::{query_all, read_servers, Server},
+    cli::BatchSource,
+    config::Config,
+    http,
+    render::field_line,
+};
+
+use colored::Colorize;
+use motd::{tr, Edition, QueryError, QueryOptions, ServerStatus};
+use tiny_http::Request;
+
+use std::{
+    fmt::Write,
+    process::exit,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// 处理抓取请求的线程数，Prometheus 通常只会同时发起一次抓取
+const WORKERS: usize = 4;
+/// 未指定 `--timeout` 时的查询时限，短于 Prometheus 默认 10 秒的抓取超时
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(8);
+/// 按抓取超时限制查询时限时，为生成与传输响应留出的时间
+const SCRAPE_MARGIN: Duration = Duration::from_millis(500);
+
+/// 从查询结果中取出指标的值，没有数据时不输出该样本
+type Extract = fn(&ServerStatus) -> Option<f64>;
+
+/// 一次探测的结果与耗时
+struct Probe {
+    result: Result<ServerStatus, QueryError>,
+    duration: Duration,
+}
+
+/// 监听 `listen` 并提供 `/metrics`，未指定 `interval` 时每次抓取都重新查询
+pub fn run(
+    source: &BatchSource,
+    listen: &str,
+    interval: Option<Duration>,
+    parallel: usize,
+    options: &QueryOptions,
+    config: &Config,
+) {
+    let servers = read_servers(source, config);
+    let server = tiny_http::Server::http(listen).unwrap_or_else(|e| {
+        println!(
+            "{}\n{}",
+            tr!("无法监听 {}", listen).bright_red().bold(),
+            e.to_string().bright_red()
+        );
+        exit(1);
+    });
+    println!(
+        "{}",
+        field_line("监听", format!("http://{}/metrics", listen).bright_cyan())
+    );
+    println!(
+        "{}",
+        field_line("服务器", servers.len().to_string().bright_green())
+    );
+
+    // 定时模式下抓取时直接返回上一次探测的结果
+    let cache = interval.map(|_| Mutex::new(metrics(&servers, parallel, options)));
+    thread::scope(|scope| {
+        if let (Some(interval), Some(cache)) = (interval, &cache) {
+            let servers = &servers;
+            scope.spawn(move || loop {
+                thread::sleep(interval);
+                let text = metrics(servers, parallel, options);
+                *cache.lock().unwrap() = text;
+            });
+        }
+        http::serve(&server, WORKERS, |request| {
+            let body = match request.url().split('?').next() {
+                Some("/metrics") => match &cache {
+                    Some(cache) => cache.lock().unwrap().clone(),
+                    None => {
+                        // 探测需在 Prometheus 放弃本次抓取前结束
+                        let timeout = scrape_timeout(&request).map_or(options.timeout, |scrape| {
+                            options
+                                .timeout
+                                .min(scrape.saturating_sub(SCRAPE_MARGIN).max(scrape / 2))
+                        });
+                        let options = QueryOptions {
+                            timeout,
+                            ..options.clone()
+                        };
+                        metrics(&servers, parallel, &options)
+                    }
+                },
+                Some("/") => {
+                    respond(request, 200, "Motd Exporter\n/metrics\n".to_string());
+                    return;
+                }
+                _ => {
+                    respond(request, 404, "Not Found\n".to_string());
+                    return;
+                }
+            };
+            respond(request, 200, body);
+        });
+    });
+}
+
+/// Prometheus 在抓取请求的头部中给出本次抓取的超时时间
+fn scrape_timeout(request: &Request) -> Option<Duration> {
+    let header = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("X-Prometheus-Scrape-Timeout-Seconds"))?;
+    match header.value.as_str().trim().parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => {
+            Some(Duration::from_secs_f64(seconds))
+        }
+        _ => None,
+    }
+}
+
+fn respond(request: Request, status: u16, body: String) {
+    http::respond(
+        request,
+        status,
+        &[("Content-Type", CONTENT_TYPE)],
+        body.into_bytes(),
+    );
+}
+
+/// 查询所有服务器并生成 Prometheus 文本格式的指标
+fn metrics(servers: &[Server], parallel: usize, options: &QueryOptions) -> String {
+    let probes = query_all(servers, parallel, |server| {
+        let start = Instant::now();
+        let result = server.query(options);
+        Probe {
+            result,
+            duration: start.elapsed(),
+        }
+    });
+    let mut text = String::new();
+
+    // 查询成功时为响应的版本，失败时为指定的版本，未指定时不带 edition 标签
+    let editions: Vec<Option<Edition>> = probes
+        .iter()
+        .map(|probe| match &probe.result {
+            Ok(status) => Some(status.edition),
+            Err(_) => options.edition,
+        })
+        .collect();
+
+    family(&mut text, "motd_up", "Whether the server responded.");
+    for ((server, probe), &edition) in servers.iter().zip(&probes).zip(&editions) {
+        let up = if probe.result.is_ok() { 1 } else { 0 };
+        sample(&mut text, "motd_up", &labels(server, edition), up);
+    }
+
+    let status_metrics: [(&str, &str, Extract); 4] = [
+        (
+            "motd_players_online",
+            "Number of players online.",
+            |status| Some(status.players_online as f64),
+        ),
+        ("motd_players_max", "Maximum number of players.", |status| {
+            Some(status.players_max as f64)
+        }),
+        (
+            "motd_latency_seconds",
+            "Round trip time of the ping packet.",
+            |status| status.latency.map(|latency| latency.as_secs_f64()),
+        ),
+        (
+            "motd_protocol_version",
+            "Protocol version reported by the server.",
+            |status| Some(status.protocol as f64),
+        ),
+    ];
+    for (name, help, value) in status_metrics {
+        family(&mut text, name, help);
+        for (server, probe) in servers.iter().zip(&probes) {
+            let Ok(status) = &probe.result else {
+                continue;
+            };
+            if let Some(value) = value(status) {
+                sample(
+                    &mut text,
+                    name,
+                    &labels(server, Some(status.edition)),
+                    value,
+                );
+            }
+        }
+    }
+
+    family(
+        &mut text,
+        "motd_probe_duration_seconds",
+        "Time taken by the whole query.",
+    );
+    for ((server, probe), &edition) in servers.iter().zip(&probes).zip(&editions) {
+        sample(
+            &mut text,
+            "motd_probe_duration_seconds",
+            &labels(server, edition),
+            probe.duration.as_secs_f64(),
+        );
+    }
+    text
+}
+
+fn family(text: &mut String, name: &str, help: &str) {
+    let _ = writeln!(text, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+}
+
+fn sample(text: &mut String, name: &str, labels: &str, value: impl Into<f64>) {
+    let _ = writeln!(text, "{}{{{}}} {}", name, labels, value.into());
+}
+
+fn labels(server: &Server, edition: Option<Edition>) -> String {
+    let mut labels = format!("server=\"{}\"", escape(&server.line));
+    if let Some(edition) = edition {
+        let edition = match edition {
+            Edition::Java => "java",
+            Edition::Bedrock => "bedrock",
+        };
+        labels.push_str(&format!(",edition=\"{}\"", edition));
+    }
+    labels
+}
+
+/// 标签值中的反斜杠、双引号与换行需要转义
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
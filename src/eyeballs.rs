@@ -0,0 +1,149 @@
+use crate::tr;
+
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{mpsc, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// RFC 8305 建议的连接尝试间隔
+const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// 按 RFC 8305 交替排列 IPv6 与 IPv4 地址，以系统解析结果中第一个地址的地址族开头
+pub fn interleave(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let Some(first) = addrs.first() else {
+        return addrs;
+    };
+    let prefer_v6 = first.is_ipv6();
+    let (mut preferred, mut other): (Vec<_>, Vec<_>) = addrs
+        .into_iter()
+        .partition(|addr| addr.is_ipv6() == prefer_v6);
+    preferred.reverse();
+    other.reverse();
+    let mut sorted = Vec::with_capacity(preferred.len() + other.len());
+    while let Some(addr) = preferred.pop() {
+        sorted.push(addr);
+        sorted.extend(other.pop());
+    }
+    sorted.extend(other.into_iter().rev());
+    sorted
+}
+
+/// 依次对每个地址发起尝试，前一次尝试未在 250ms 内完成或已经失败时立即开始下一次，
+/// 返回最先成功的地址与结果，全部失败时返回最后一个错误
+///
+/// 落败的尝试会在各自的超时后自行结束
+pub fn race<T, F>(
+    addrs: &[SocketAddr],
+    timeout: Duration,
+    attempt: F,
+) -> io::Result<(SocketAddr, T)>
+where
+    T: Send + 'static,
+    F: Fn(SocketAddr, Duration) -> io::Result<T> + Send + Sync + 'static,
+{
+    let deadline = Instant::now() + timeout;
+    let attempt = Arc::new(attempt);
+    let (tx, rx) = mpsc::channel();
+    let mut candidates = addrs.iter().copied();
+    let mut pending = 0;
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, tr!("没有可用的地址"));
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        let wait = match candidates.next() {
+            Some(addr) => {
+                let (tx, attempt) = (tx.clone(), attempt.clone());
+                thread::spawn(move || {
+                    let _ = tx.send((addr, attempt(addr, remaining)));
+                });
+                pending += 1;
+                ATTEMPT_DELAY.min(remaining)
+            }
+            None if pending == 0 => return Err(last_err),
+            None => remaining,
+        };
+        match rx.recv_timeout(wait) {
+            Ok((addr, Ok(result))) => return Ok((addr, result)),
+            Ok((_, Err(e))) => {
+                pending -= 1;
+                last_err = e;
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addrs(addrs: &[&str]) -> Vec<SocketAddr> {
+        addrs.iter().map(|addr| addr.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn interleave_starts_with_first_family() {
+        let sorted = interleave(addrs(&[
+            "[2001:db8::1]:25565",
+            "[2001:db8::2]:25565",
+            "192.0.2.1:25565",
+            "192.0.2.2:25565",
+        ]));
+        assert_eq!(
+            sorted,
+            addrs(&[
+                "[2001:db8::1]:25565",
+                "192.0.2.1:25565",
+                "[2001:db8::2]:25565",
+                "192.0.2.2:25565",
+            ])
+        );
+    }
+
+    #[test]
+    fn interleave_appends_remaining_in_order() {
+        // 每个地址族内保持系统解析结果的顺序，多出的地址排在最后
+        let sorted = interleave(addrs(&[
+            "192.0.2.1:25565",
+            "[2001:db8::1]:25565",
+            "192.0.2.2:25565",
+            "192.0.2.3:25565",
+        ]));
+        assert_eq!(
+            sorted,
+            addrs(&[
+                "192.0.2.1:25565",
+                "[2001:db8::1]:25565",
+                "192.0.2.2:25565",
+                "192.0.2.3:25565",
+            ])
+        );
+        let sorted = interleave(addrs(&[
+            "192.0.2.1:25565",
+            "[2001:db8::1]:25565",
+            "[2001:db8::2]:25565",
+            "[2001:db8::3]:25565",
+        ]));
+        assert_eq!(
+            sorted,
+            addrs(&[
+                "192.0.2.1:25565",
+                "[2001:db8::1]:25565",
+                "[2001:db8::2]:25565",
+                "[2001:db8::3]:25565",
+            ])
+        );
+    }
+
+    #[test]
+    fn interleave_single_family() {
+        let single = addrs(&["192.0.2.1:25565", "192.0.2.2:25565"]);
+        assert_eq!(interleave(single.clone()), single);
+        assert!(interleave(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::Edition;
+
+use std::collections::HashMap;
+
+/// `§` 颜色代码，基岩版另有 `g` 至 `u` 的材质颜色
+pub fn mc_formatting_colors_by_ss(edition: Edition) -> HashMap<char, (u8, u8, u8)> {
+    let java = [
+        ('0', (0, 0, 0)),
+        ('1', (0, 0, 170)),
+        ('2', (0, 170, 0)),
+        ('3', (0, 170, 170)),
+        ('4', (170, 0, 0)),
+        ('5', (170, 0, 170)),
+        ('6', (255, 170, 0)),
+        ('7', (170, 170, 170)),
+        ('8', (85, 85, 85)),
+        ('9', (85, 85, 255)),
+        ('a', (85, 255, 85)),
+        ('b', (85, 255, 255)),
+        ('c', (255, 85, 85)),
+        ('d', (255, 85, 255)),
+        ('e', (255, 255, 85)),
+        ('f', (255, 255, 255)),
+    ];
+    let bedrock = [
+        ('g', (221, 214, 5)),
+        ('h', (227, 212, 209)),
+        ('i', (206, 202, 202)),
+        ('j', (68, 58, 59)),
+        ('m', (151, 22, 7)),
+        ('n', (180, 104, 77)),
+        ('p', (222, 177, 45)),
+        ('q', (17, 160, 54)),
+        ('s', (44, 186, 168)),
+        ('t', (33, 73, 123)),
+        ('u', (154, 92, 198)),
+    ];
+    match edition {
+        Edition::Java => java.into_iter().collect(),
+        Edition::Bedrock => java.into_iter().chain(bedrock).collect(),
+    }
+}
+
+pub fn mc_formatting_colors_by_name() -> HashMap<&'static str, (u8, u8, u8)> {
+    [
+        ("black", (0, 0, 0)),
+        ("dark_blue", (0, 0, 170)),
+        ("dark_green", (0, 170, 0)),
+        ("dark_aqua", (0, 170, 170)),
+        ("dark_red", (170, 0, 0)),
+        ("dark_purple", (170, 0, 170)),
+        ("gold", (255, 170, 0)),
+        ("gray", (170, 170, 170)),
+        ("dark_gray", (85, 85, 85)),
+        ("blue", (85, 85, 255)),
+        ("green", (85, 255, 85)),
+        ("aqua", (85, 255, 255)),
+        ("red", (255, 85, 85)),
+        ("light_purple", (255, 85, 255)),
+        ("yellow", (255, 255, 85)),
+        ("white", (255, 255, 255)),
+        ("minecoin_gold", (221, 214, 5)),
+        ("material_quartz", (227, 212, 209)),
+        ("material_iron", (206, 202, 202)),
+        ("material_netherite", (68, 58, 59)),
+        ("material_redstone", (151, 22, 7)),
+        ("material_copper", (180, 104, 77)),
+        ("material_gold", (222, 177, 45)),
+        ("material_emerald", (17, 160, 54)),
+        ("material_diamond", (44, 186, 168)),
+        ("material_lapis", (33, 73, 123)),
+        ("material_amethyst", (154, 92, 198)),
+    ]
+    .iter()
+    .cloned()
+    .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MCFontFormattingStyle {
+    Obfuscated,
+    Bold,
+    Strikethrough,
+    Underline,
+    Italic,
+    Clear,
+}
+
+/// `§` 格式代码，基岩版中 `m` 与 `n` 为颜色，没有删除线与下划线
+pub fn mc_formatting_styles(edition: Edition) -> HashMap<char, MCFontFormattingStyle> {
+    [
+        ('k', MCFontFormattingStyle::Obfuscated),
+        ('l', MCFontFormattingStyle::Bold),
+        ('m', MCFontFormattingStyle::Strikethrough),
+        ('n', MCFontFormattingStyle::Underline),
+        ('o', MCFontFormattingStyle::Italic),
+        ('r', MCFontFormattingStyle::Clear),
+    ]
+    .into_iter()
+    .filter(|(code, _)| edition == Edition::Java || !matches!(code, 'm' | 'n'))
+    .collect()
+}
+
+pub fn ss() -> char {
+    '§'
+}
+
+/// 去除文本中的 `§` 格式代码
+pub fn strip_formatting(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == ss() {
+            chars.next();
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
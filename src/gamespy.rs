@@ -0,0 +1,201 @@
+use crate::{
+    net::{connect_udp, invalid_data},
+    tr,
+};
+
+use std::{
+    collections::HashMap,
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const MAGIC: [u8; 2] = [0xfe, 0xfd];
+const TYPE_HANDSHAKE: u8 = 0x09;
+const TYPE_STAT: u8 = 0x00;
+
+/// 开启 `enable-query` 的服务器通过 GameSpy4 协议返回的完整信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FullStat {
+    /// 服务端软件，例如 `CraftBukkit on Bukkit 1.20.4`
+    pub software: Option<String>,
+    pub plugins: Vec<String>,
+    pub map: Option<String>,
+    pub game_type: Option<String>,
+    pub version: Option<String>,
+    /// 完整的在线玩家列表
+    pub players: Vec<String>,
+    /// 服务器返回的全部键值对
+    pub fields: HashMap<String, String>,
+}
+
+/// 完成握手后发送 full stat 请求
+pub fn full_stat(address: SocketAddr, timeout: Duration) -> io::Result<FullStat> {
+    let socket = connect_udp(address, timeout)?;
+
+    // 会话 ID 每个字节只能使用低 4 位
+    let session = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.subsec_nanos())
+        .unwrap_or_default()
+        & 0x0f0f0f0f;
+
+    let mut handshake = MAGIC.to_vec();
+    handshake.push(TYPE_HANDSHAKE);
+    handshake.extend_from_slice(&session.to_be_bytes());
+    socket.send(&handshake)?;
+    let response = receive(&socket, TYPE_HANDSHAKE, session)?;
+    let token: i32 = read_cstring(&response, &mut 0)?
+        .parse()
+        .map_err(|_| invalid_data(tr!("Query 握手响应中的令牌不合法")))?;
+
+    let mut request = MAGIC.to_vec();
+    request.push(TYPE_STAT);
+    request.extend_from_slice(&session.to_be_bytes());
+    request.extend_from_slice(&token.to_be_bytes());
+    // 附加 4 字节填充即为 full stat 请求
+    request.extend_from_slice(&[0, 0, 0, 0]);
+    socket.send(&request)?;
+    let response = receive(&socket, TYPE_STAT, session)?;
+
+    parse_full_stat(&response)
+}
+
+fn receive(socket: &UdpSocket, kind: u8, session: u32) -> io::Result<Vec<u8>> {
+    let mut buf = [0u8; 65535];
+    loop {
+        let len = socket.recv(&mut buf)?;
+        if len >= 5 && buf[0] == kind && buf[1..5] == session.to_be_bytes() {
+            return Ok(buf[5..len].to_vec());
+        }
+    }
+}
+
+fn parse_full_stat(data: &[u8]) -> io::Result<FullStat> {
+    // 跳过固定的 "splitnum\0\x80\0" 填充
+    let mut pos = 11;
+    let mut fields = HashMap::new();
+    loop {
+        let key = read_cstring(data, &mut pos)?;
+        if key.is_empty() {
+            break;
+        }
+        let value = read_cstring(data, &mut pos)?;
+        fields.insert(key, value);
+    }
+
+    // 跳过固定的 "\x01player_\0\0" 填充
+    pos += 10;
+    let mut players = Vec::new();
+    loop {
+        let player = read_cstring(data, &mut pos)?;
+        if player.is_empty() {
+            break;
+        }
+        players.push(player);
+    }
+
+    // plugins 字段形如 "服务端: 插件1 版本; 插件2 版本"，原版服务器为空
+    let (software, plugins) = match fields.get("plugins").map(String::as_str) {
+        None | Some("") => (None, Vec::new()),
+        Some(plugins) => match plugins.split_once(": ") {
+            Some((software, plugins)) => (
+                Some(software.to_string()),
+                plugins.split("; ").map(str::to_string).collect(),
+            ),
+            None => (Some(plugins.to_string()), Vec::new()),
+        },
+    };
+    let field = |name: &str| fields.get(name).filter(|v| !v.is_empty()).cloned();
+    Ok(FullStat {
+        software,
+        plugins,
+        map: field("map"),
+        game_type: field("gametype"),
+        version: field("version"),
+        players,
+        fields,
+    })
+}
+
+fn read_cstring(data: &[u8], pos: &mut usize) -> io::Result<String> {
+    let rest = data
+        .get(*pos..)
+        .ok_or_else(|| invalid_data(tr!("Query 响应不完整")))?;
+    let len = rest
+        .iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| invalid_data(tr!("Query 响应不完整")))?;
+    *pos += len + 1;
+    Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    /// Paper 1.20.4 的 full stat 响应 (去除类型与会话 ID)
+    const FULL_STAT: &[u8] = b"splitnum\x00\x80\x00\
+hostname\x00A Minecraft Server\x00gametype\x00SMP\x00game_id\x00MINECRAFT\x00\
+version\x001.20.4\x00plugins\x00Paper on 1.20.4-R0.1-SNAPSHOT: LuckPerms 5.4.102; Vault 1.7.3\x00\
+map\x00world\x00numplayers\x002\x00maxplayers\x0020\x00hostport\x0025565\x00\
+hostip\x00127.0.0.1\x00\x00\x01player_\x00\x00Steve\x00Alex\x00\x00";
+
+    #[test]
+    fn parse_paper() {
+        let stat = parse_full_stat(FULL_STAT).unwrap();
+        assert_eq!(
+            stat.software.as_deref(),
+            Some("Paper on 1.20.4-R0.1-SNAPSHOT")
+        );
+        assert_eq!(stat.plugins, ["LuckPerms 5.4.102", "Vault 1.7.3"]);
+        assert_eq!(stat.map.as_deref(), Some("world"));
+        assert_eq!(stat.game_type.as_deref(), Some("SMP"));
+        assert_eq!(stat.version.as_deref(), Some("1.20.4"));
+        assert_eq!(stat.players, ["Steve", "Alex"]);
+        assert_eq!(stat.fields["numplayers"], "2");
+    }
+
+    #[test]
+    fn parse_vanilla_without_plugins() {
+        let data =
+            b"splitnum\x00\x80\x00plugins\x00\x00map\x00world\x00\x00\x01player_\x00\x00\x00";
+        let stat = parse_full_stat(data).unwrap();
+        assert_eq!(stat.software, None);
+        assert!(stat.plugins.is_empty());
+        assert!(stat.players.is_empty());
+    }
+
+    #[test]
+    fn parse_truncated() {
+        for len in 0..FULL_STAT.len() {
+            assert!(parse_full_stat(&FULL_STAT[..len]).is_err(), "{} 字节", len);
+        }
+    }
+
+    #[test]
+    fn full_stat_stub_server() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0u8; 64];
+            let (_, peer) = socket.recv_from(&mut buf).unwrap();
+            let mut response = vec![TYPE_HANDSHAKE];
+            response.extend_from_slice(&buf[3..7]);
+            response.extend_from_slice(b"9513307\x00");
+            socket.send_to(&response, peer).unwrap();
+
+            let (len, peer) = socket.recv_from(&mut buf).unwrap();
+            assert_eq!(buf[7..11], 9513307i32.to_be_bytes());
+            assert_eq!(len, 15);
+            let mut response = vec![TYPE_STAT];
+            response.extend_from_slice(&buf[3..7]);
+            response.extend_from_slice(FULL_STAT);
+            socket.send_to(&response, peer).unwrap();
+        });
+        let stat = full_stat(address, Duration::from_secs(2)).unwrap();
+        assert_eq!(stat.players, ["Steve", "Alex"]);
+    }
+}
@@ -0,0 +1,250 @@
+use base64::prelude::*;
+use crossterm::terminal;
+use image::{imageops::FilterType, load_from_memory, GenericImageView as _};
+use motd::tr;
+
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    io::{self, IsTerminal, Read, Write},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+/// 等待终端回应 DA1 查询的时间
+const DA1_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 显示服务器图标的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    Kitty,
+    ITerm2,
+    Sixel,
+    /// 以 `▀` 字符拼出图像，所有支持真彩色的终端均可显示
+    HalfBlocks,
+}
+
+impl ImageProtocol {
+    /// 根据终端设置的环境变量判断支持的图像协议
+    pub fn detect() -> Self {
+        if !std::io::stdout().is_terminal() {
+            return ImageProtocol::HalfBlocks;
+        }
+        let var = |name: &str| env::var(name).unwrap_or_default();
+        let set = |name: &str| env::var_os(name).is_some_and(|value| !value.is_empty());
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        // tmux 与 screen 不会转发图像协议
+        if set("TMUX") || term.starts_with("screen") {
+            return ImageProtocol::HalfBlocks;
+        }
+        if set("KITTY_WINDOW_ID")
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            ImageProtocol::Kitty
+        } else if program == "iTerm.app" || var("LC_TERMINAL") == "iTerm2" {
+            ImageProtocol::ITerm2
+        } else if term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term == "contour"
+            // Windows Terminal 从 1.22 起才支持 Sixel，需要向终端确认
+            || (set("WT_SESSION") && sixel_supported())
+        {
+            ImageProtocol::Sixel
+        } else {
+            ImageProtocol::HalfBlocks
+        }
+    }
+}
+
+/// 发送 DA1 (Primary Device Attributes) 查询，终端回应的属性中含有 4 时支持 Sixel，
+/// 未在 [`DA1_TIMEOUT`] 内回应时视为不支持
+fn sixel_supported() -> bool {
+    if !io::stdin().is_terminal() || terminal::enable_raw_mode().is_err() {
+        return false;
+    }
+    let mut stdout = io::stdout();
+    let reply = match stdout.write_all(b"\x1b[c").and_then(|()| stdout.flush()) {
+        Ok(()) => read_reply(),
+        Err(_) => None,
+    };
+    let _ = terminal::disable_raw_mode();
+    reply.is_some_and(|reply| reports_sixel(&reply))
+}
+
+/// 在后台线程读取标准输入，终端不回应时读取线程会一直阻塞，因此只等待有限的时间
+fn read_reply() -> Option<String> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut reply = Vec::new();
+        for byte in io::stdin().lock().bytes() {
+            let Ok(byte) = byte else {
+                break;
+            };
+            reply.push(byte);
+            // 回应以 c 结尾，之前可能混有用户的输入
+            if byte == b'c' && reply.windows(3).any(|window| window == b"\x1b[?") {
+                break;
+            }
+        }
+        let _ = tx.send(reply);
+    });
+    let reply = rx.recv_timeout(DA1_TIMEOUT).ok()?;
+    Some(String::from_utf8_lossy(&reply).into_owned())
+}
+
+/// 回应的格式为 `ESC [ ? 属性;属性;... c`
+fn reports_sixel(reply: &str) -> bool {
+    reply
+        .rsplit_once("\x1b[?")
+        .and_then(|(_, attributes)| attributes.strip_suffix('c'))
+        .is_some_and(|attributes| attributes.split(';').any(|attribute| attribute == "4"))
+}
+
+/// 生成在当前光标位置占据 `columns`×`rows` 个字符格显示图像的转义序列
+pub fn image_escape(
+    protocol: ImageProtocol,
+    png: &[u8],
+    columns: u16,
+    rows: u16,
+) -> Result<String, Box<dyn Error>> {
+    match protocol {
+        ImageProtocol::Kitty => Ok(kitty(png, columns, rows)),
+        ImageProtocol::ITerm2 => Ok(format!(
+            "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+            png.len(),
+            columns,
+            rows,
+            BASE64_STANDARD.encode(png)
+        )),
+        ImageProtocol::Sixel => sixel(png, columns, rows),
+        ImageProtocol::HalfBlocks => Err(tr!("半块字符不使用转义序列").into()),
+    }
+}
+
+/// Kitty 图形协议要求将 base64 数据拆分为不超过 4096 字节的块
+fn kitty(png: &[u8], columns: u16, rows: u16) -> String {
+    let data = BASE64_STANDARD.encode(png);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    let mut escape = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        let chunk = std::str::from_utf8(chunk).unwrap();
+        if index == 0 {
+            escape.push_str(&format!(
+                "\x1b_Ga=T,f=100,q=2,c={},r={},m={};{}\x1b\\",
+                columns, rows, more, chunk
+            ));
+        } else {
+            escape.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    escape
+}
+
+/// 将图像缩放到字符格对应的像素大小后编码为 Sixel，颜色量化为 6×6×6 的调色板
+fn sixel(png: &[u8], columns: u16, rows: u16) -> Result<String, Box<dyn Error>> {
+    let (cell_width, cell_height) = cell_size();
+    let width = (columns as u32 * cell_width).max(1);
+    let height = (rows as u32 * cell_height).max(1);
+    let image = load_from_memory(png)?.resize(width, height, FilterType::CatmullRom);
+    let (width, height) = image.dimensions();
+
+    // 透明像素不绘制，保留终端背景
+    let mut indexes = vec![None; (width * height) as usize];
+    for (x, y, pixel) in image.pixels() {
+        if pixel[3] >= 128 {
+            let level = |value: u8| (value as u16 * 5 / 255) as u8;
+            indexes[(y * width + x) as usize] =
+                Some(level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]));
+        }
+    }
+
+    let mut escape = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    for index in 0..216u16 {
+        let percent = |level: u16| level * 100 / 5;
+        escape.push_str(&format!(
+            "#{};2;{};{};{}",
+            index,
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        ));
+    }
+    for band in (0..height).step_by(6) {
+        // 记录本条带内每种颜色在每一列上占据的行
+        let mut colors: HashMap<u8, Vec<u8>> = HashMap::new();
+        for dy in 0..6.min(height - band) {
+            for x in 0..width {
+                if let Some(color) = indexes[((band + dy) * width + x) as usize] {
+                    colors
+                        .entry(color)
+                        .or_insert_with(|| vec![0; width as usize])[x as usize] |= 1 << dy;
+                }
+            }
+        }
+        let mut colors: Vec<_> = colors.into_iter().collect();
+        colors.sort_by_key(|(color, _)| *color);
+        for (color, bits) in colors {
+            escape.push_str(&format!("#{}", color));
+            push_sixel_run(&mut escape, &bits);
+            escape.push('$');
+        }
+        escape.push('-');
+    }
+    escape.push_str("\x1b\\");
+    Ok(escape)
+}
+
+/// 对相同的 Sixel 字符使用 `!` 游程编码
+fn push_sixel_run(escape: &mut String, bits: &[u8]) {
+    let mut index = 0;
+    while index < bits.len() {
+        let run = bits[index..]
+            .iter()
+            .take_while(|&&bit| bit == bits[index])
+            .count();
+        let c = (bits[index] + 63) as char;
+        if run > 3 {
+            escape.push_str(&format!("!{}{}", run, c));
+        } else {
+            escape.extend(std::iter::repeat_n(c, run));
+        }
+        index += run;
+    }
+}
+
+/// 终端不报告窗口像素大小时按常见的 10×20 估算
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as u32,
+            (size.height / size.rows) as u32,
+        ),
+        _ => (10, 20),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn da1_reply_with_sixel() {
+        // Windows Terminal 1.22 的回应
+        assert!(reports_sixel("\x1b[?61;4;6;7;14;21;22;23;24;28;32;42;52c"));
+        assert!(reports_sixel("abc\x1b[?62;4c"));
+    }
+
+    #[test]
+    fn da1_reply_without_sixel() {
+        assert!(!reports_sixel("\x1b[?61;6;7;14;21;22;23;24;28;32;42c"));
+        assert!(!reports_sixel("\x1b[?64;44c"));
+        assert!(!reports_sixel("\x1b[?62;4"));
+        assert!(!reports_sixel(""));
+    }
+}
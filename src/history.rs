@@ -0,0 +1,331 @@
+use crate::{
+    cli::StoreFormat,
+    color::plain_output,
+    render::{field_line, label_width},
+    store::{load, Filter, Record},
+};
+
+use chrono::{DateTime, Local, Timelike};
+use colored::{ColoredString, Colorize};
+use motd::tr;
+use serde_json::{json, Value};
+
+use std::{collections::BTreeMap, path::Path, process::exit};
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// 横向柱状图的八分之一格
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+const MAX_CHART_WIDTH: usize = 60;
+const HOUR_BAR_WIDTH: usize = 20;
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// 一个服务器在时间范围内的统计
+struct Summary<'a> {
+    server: &'a str,
+    records: Vec<&'a Record>,
+    uptime: f64,
+    /// 最早达到最高人数的记录
+    peak: Option<&'a Record>,
+    average_latency: Option<f64>,
+    /// 时间与变更前后的版本
+    version_changes: Vec<(DateTime<Local>, &'a str, &'a str)>,
+    /// 每个小时的平均在线人数，没有在线记录的小时为 `None`
+    hourly: [Option<f64>; 24],
+}
+
+pub fn run(path: &Path, format: StoreFormat, filter: &Filter, json: bool) {
+    let records = load(path, format, filter).unwrap_or_else(|e| {
+        println!(
+            "{}\n{}",
+            tr!("历史记录读取失败").bright_red().bold(),
+            e.bright_red()
+        );
+        exit(7);
+    });
+    let mut servers: BTreeMap<&str, Vec<&Record>> = BTreeMap::new();
+    for record in &records {
+        servers.entry(&record.server).or_default().push(record);
+    }
+    let summaries: Vec<Summary> = servers
+        .into_iter()
+        .map(|(server, records)| summarize(server, records))
+        .collect();
+
+    if json {
+        let documents: Vec<Value> = summaries.iter().map(summary_to_json).collect();
+        println!("{}", serde_json::to_string_pretty(&documents).unwrap());
+        return;
+    }
+    if summaries.is_empty() {
+        println!("{}", tr!("没有符合条件的记录").bright_yellow());
+        return;
+    }
+    for (index, summary) in summaries.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        print_summary(summary);
+    }
+}
+
+fn summarize<'a>(server: &'a str, records: Vec<&'a Record>) -> Summary<'a> {
+    let online: Vec<&Record> = records.iter().copied().filter(|r| r.online).collect();
+    let uptime = online.len() as f64 / records.len() as f64;
+    // 反向查找使相同人数时取最早的一条
+    let peak = online
+        .iter()
+        .rev()
+        .max_by_key(|r| r.players_online)
+        .copied();
+    let latencies: Vec<u64> = online.iter().filter_map(|r| r.latency_ms).collect();
+    let average_latency = (!latencies.is_empty())
+        .then(|| latencies.iter().sum::<u64>() as f64 / latencies.len() as f64);
+
+    let mut version_changes = Vec::new();
+    let mut current: Option<&str> = None;
+    for record in &online {
+        let Some(version) = record.version.as_deref() else {
+            continue;
+        };
+        if let Some(previous) = current.filter(|&previous| previous != version) {
+            version_changes.push((record.time, previous, version));
+        }
+        current = Some(version);
+    }
+
+    let mut totals = [(0u64, 0u64); 24];
+    for record in &online {
+        let (sum, count) = &mut totals[record.time.hour() as usize];
+        *sum += record.players_online as u64;
+        *count += 1;
+    }
+    let hourly = totals.map(|(sum, count)| (count > 0).then(|| sum as f64 / count as f64));
+
+    Summary {
+        server,
+        records,
+        uptime,
+        peak,
+        average_latency,
+        version_changes,
+        hourly,
+    }
+}
+
+fn print_summary(summary: &Summary) {
+    let (first, last) = (
+        summary.records[0],
+        summary.records[summary.records.len() - 1],
+    );
+    let width = chart_width(summary.records.len());
+    let line = |label: &'static str, value: ColoredString| println!("{}", field_line(label, value));
+
+    line("服务器", summary.server.bright_yellow());
+    line(
+        "时间范围",
+        format!(
+            "{} ~ {} · {}",
+            first.time.format(TIME_FORMAT),
+            last.time.format(TIME_FORMAT),
+            tr!("{} 条记录", summary.records.len())
+        )
+        .normal(),
+    );
+    let uptime = format!("{:.1}%", summary.uptime * 100.0);
+    line(
+        "在线率",
+        match summary.uptime {
+            ratio if ratio >= 0.99 => uptime.bright_green(),
+            ratio if ratio >= 0.9 => uptime.bright_yellow(),
+            _ => uptime.bright_red(),
+        },
+    );
+    line("在线时间轴", timeline(&summary.records, width).normal());
+    match summary.peak {
+        Some(peak) => line(
+            "最高人数",
+            format!(
+                "{}/{} · {}",
+                peak.players_online,
+                peak.players_max,
+                peak.time.format(TIME_FORMAT)
+            )
+            .bright_green(),
+        ),
+        None => line("最高人数", "-".normal()),
+    }
+    if let Some(latency) = summary.average_latency {
+        line("平均延迟", format!("{:.0} ms", latency).normal());
+    }
+    let (trend, max) = trend(&summary.records, width);
+    line(
+        "人数趋势",
+        format!(
+            "{} {}",
+            trend.bright_green(),
+            format!("(0 - {})", max).dimmed()
+        )
+        .normal(),
+    );
+
+    if summary.version_changes.is_empty() {
+        let version = summary
+            .records
+            .iter()
+            .rev()
+            .find_map(|record| record.version.as_deref())
+            .unwrap_or("-");
+        line("版本变更", tr!("无，始终为 {}", version).normal());
+    }
+    for (index, (time, from, to)) in summary.version_changes.iter().enumerate() {
+        let value = format!(
+            "{} · {} → {}",
+            time.format(TIME_FORMAT),
+            from.bright_red(),
+            to.bright_green()
+        );
+        if index == 0 {
+            line("版本变更", value.normal());
+        } else {
+            println!("{}", continuation(&value));
+        }
+    }
+
+    // 按小时统计平均在线人数，找出最热闹的时段，没有在线记录的小时不显示
+    let busiest = summary.hourly.iter().flatten().copied().fold(0.0, f64::max);
+    let hours = summary
+        .hourly
+        .iter()
+        .enumerate()
+        .filter_map(|(hour, average)| average.map(|average| (hour, average)));
+    for (index, (hour, average)) in hours.enumerate() {
+        let bar = bar(average, busiest);
+        let padding = " ".repeat(HOUR_BAR_WIDTH + 1 - bar.chars().count());
+        let value = format!(
+            "{:02}:00 {}{}{:.1}",
+            hour,
+            bar.bright_green(),
+            padding,
+            average
+        );
+        if index == 0 {
+            line("时段分布", value.normal());
+        } else {
+            println!("{}", continuation(&value));
+        }
+    }
+}
+
+/// 图表的列数，不超过终端宽度与记录数
+fn chart_width(records: usize) -> usize {
+    let columns = crossterm::terminal::size().map_or(80, |(columns, _)| columns as usize);
+    columns
+        .saturating_sub(label_width() + 3 + 12)
+        .clamp(10, MAX_CHART_WIDTH)
+        .min(records)
+}
+
+/// 将记录按时间均分到 `width` 个区间，没有记录的区间为空
+fn buckets<'a>(records: &[&'a Record], width: usize) -> Vec<Vec<&'a Record>> {
+    let mut buckets = vec![Vec::new(); width];
+    let (first, last) = (
+        records[0].time.timestamp(),
+        records[records.len() - 1].time.timestamp(),
+    );
+    let span = (last - first + 1) as f64;
+    for record in records {
+        let index = ((record.time.timestamp() - first) as f64 / span * width as f64) as usize;
+        buckets[index.min(width - 1)].push(*record);
+    }
+    buckets
+}
+
+/// 每个区间全部在线、部分在线、全部离线时分别使用不同的字符与颜色
+fn timeline(records: &[&Record], width: usize) -> String {
+    buckets(records, width)
+        .iter()
+        .map(|bucket| {
+            let online = bucket.iter().filter(|record| record.online).count();
+            match online {
+                _ if bucket.is_empty() => " ".normal(),
+                online if online == bucket.len() => "█".bright_green(),
+                0 => "░".bright_red(),
+                _ => "▒".bright_yellow(),
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+/// 每个区间的最高在线人数，返回图表与纵轴的最大值
+fn trend(records: &[&Record], width: usize) -> (String, u32) {
+    let peaks: Vec<Option<u32>> = buckets(records, width)
+        .iter()
+        .map(|bucket| {
+            bucket
+                .iter()
+                .filter(|record| record.online)
+                .map(|record| record.players_online)
+                .max()
+        })
+        .collect();
+    let max = peaks.iter().flatten().copied().max().unwrap_or(0);
+    let chart = peaks
+        .iter()
+        .map(|peak| match peak {
+            None => ' ',
+            Some(peak) => {
+                let level = *peak as f64 / max.max(1) as f64 * (SPARKS.len() - 1) as f64;
+                SPARKS[level.round() as usize]
+            }
+        })
+        .collect();
+    (chart, max)
+}
+
+/// 长度与 `value / max` 成正比的横向柱，精确到八分之一格
+fn bar(value: f64, max: f64) -> String {
+    if max <= 0.0 {
+        return String::new();
+    }
+    let eighths = (value / max * (HOUR_BAR_WIDTH * 8) as f64).round() as usize;
+    let mut bar = "█".repeat(eighths / 8);
+    if let Some(index) = (eighths % 8).checked_sub(1) {
+        bar.push(EIGHTHS[index]);
+    }
+    if bar.is_empty() {
+        bar.push(EIGHTHS[0]);
+    }
+    bar
+}
+
+/// 多行字段中除第一行以外的行
+fn continuation(value: &str) -> String {
+    if plain_output() {
+        return format!("  {}", value);
+    }
+    format!("{} | {}", " ".repeat(label_width()), value)
+}
+
+fn summary_to_json(summary: &Summary) -> Value {
+    let time = |time: &DateTime<Local>| time.to_rfc3339();
+    json!({
+        "server": summary.server,
+        "records": summary.records.len(),
+        "from": time(&summary.records[0].time),
+        "to": time(&summary.records[summary.records.len() - 1].time),
+        "uptime": summary.uptime,
+        "peak": summary.peak.map(|peak| json!({
+            "time": time(&peak.time),
+            "online": peak.players_online,
+            "max": peak.players_max,
+        })),
+        "average_latency_ms": summary.average_latency,
+        "version_changes": summary
+            .version_changes
+            .iter()
+            .map(|(changed, from, to)| json!({ "time": time(changed), "from": from, "to": to }))
+            .collect::<Vec<Value>>(),
+        "hourly_average_players": summary.hourly,
+    })
+}
@@ -0,0 +1,26 @@
+use tiny_http::{Header, Request, Response, Server};
+
+use std::thread;
+
+/// 由固定数量的线程处理请求，线程均忙碌时新的请求排队等待
+pub fn serve(server: &Server, workers: usize, handle: impl Fn(Request) + Sync) {
+    thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    handle(request);
+                }
+            });
+        }
+    });
+}
+
+/// 以 `headers` 回应请求
+pub fn respond(request: Request, status: u16, headers: &[(&str, &str)], body: Vec<u8>) {
+    let mut response = Response::from_data(body).with_status_code(status);
+    for (name, value) in headers {
+        response.add_header(Header::from_bytes(*name, *value).unwrap());
+    }
+    // 客户端提前断开时无需处理
+    let _ = request.respond(response);
+}
@@ -0,0 +1,391 @@
+//! 用户可见文本的多语言支持
+//!
+//! 源码中的简体中文文本即为消息的键，其他语言按键查表，没有翻译的文本原样显示
+
+use std::{
+    env,
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::ZhCn as u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    ZhCn,
+    EnUs,
+}
+
+impl Language {
+    /// 依次读取 `LC_ALL`、`LC_MESSAGES` 与 `LANG`，未设置或为 `C`、`POSIX` 时使用简体中文，
+    /// 其他非中文的语言环境使用英文
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        match locale.as_str() {
+            "" | "C" | "POSIX" => Language::ZhCn,
+            locale if locale.starts_with("C.") => Language::ZhCn,
+            locale => Language::parse(locale).unwrap_or(Language::EnUs),
+        }
+    }
+
+    /// 识别 `zh-CN`、`zh_CN.UTF-8`、`en` 等写法
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.split(['.', '@']).next().unwrap_or(name);
+        let language = name.split(['-', '_']).next().unwrap_or(name);
+        match language.to_ascii_lowercase().as_str() {
+            "zh" => Some(Language::ZhCn),
+            "en" => Some(Language::EnUs),
+            _ => None,
+        }
+    }
+}
+
+/// 设置之后所有文本使用的语言，默认为简体中文
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::EnUs,
+        _ => Language::ZhCn,
+    }
+}
+
+/// 按当前语言翻译一条消息
+pub fn tr(message: &'static str) -> &'static str {
+    match language() {
+        Language::ZhCn => message,
+        Language::EnUs => english(message),
+    }
+}
+
+/// 消息的英文文本，用于不随界面语言变化的输出
+pub fn english(message: &'static str) -> &'static str {
+    en_us(message).unwrap_or(message)
+}
+
+/// 依次用 `args` 替换 `template` 中的 `{}`
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+/// 翻译消息，带参数时与 `format!` 一样用参数依次替换 `{}`，例如 `tr!("已保存到 {}", path)`
+#[macro_export]
+macro_rules! tr {
+    ($message:literal) => {
+        $crate::i18n::tr($message)
+    };
+    ($message:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::tr($message),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
+
+fn en_us(message: &str) -> Option<&'static str> {
+    Some(match message {
+        // 查询
+        "无法解析地址: {}" => "Could not resolve the address: {}",
+        "连接被拒绝，服务器可能未开启或端口错误" => {
+            "Connection refused, the server may be offline or the port is wrong"
+        }
+        "无法建立连接: {}" => "Could not connect: {}",
+        "连接超时" => "Connection timed out",
+        "无法识别服务器的响应: {}" => "Unrecognized server response: {}",
+        "服务器在响应完成前断开了连接" => {
+            "The server closed the connection before responding"
+        }
+        "没有可用的地址" => "No usable address",
+        "不合法的域名" => "Invalid domain name",
+        "DNS 响应过短" => "DNS response is too short",
+        "DNS 服务器返回错误 {}" => "DNS server returned error {}",
+        "DNS 响应不完整" => "DNS response is incomplete",
+        "DNS 响应中存在循环压缩指针" => {
+            "DNS response contains a compression pointer loop"
+        }
+        "状态响应的数据包 ID 不正确" => "Unexpected packet ID in the status response",
+        "状态响应不完整" => "Status response is incomplete",
+        "pong 数据包不正确" => "Invalid pong packet",
+        "数据包长度不合法" => "Invalid packet length",
+        "VarInt 过长" => "VarInt is too long",
+        "legacy ping 响应的数据包 ID 不正确" => {
+            "Unexpected packet ID in the legacy ping response"
+        }
+        "legacy ping 响应的字段数量不正确" => {
+            "Unexpected number of fields in the legacy ping response"
+        }
+        "无法识别的 legacy ping 响应" => "Unrecognized legacy ping response",
+        "Unconnected Pong 的魔数不正确" => "Invalid magic in the Unconnected Pong",
+        "Unconnected Pong 不完整" => "Unconnected Pong is incomplete",
+        "服务器信息的字段数量不正确" => {
+            "Unexpected number of fields in the server info"
+        }
+        "服务器信息中的人数不合法" => "Invalid player count in the server info",
+        "Query 握手响应中的令牌不合法" => {
+            "Invalid token in the Query handshake response"
+        }
+        "Query 响应不完整" => "Query response is incomplete",
+        "图标不是 PNG 格式的 data URI" => "The icon is not a PNG data URI",
+        "图标内容不是 PNG 图片" => "The icon content is not a PNG image",
+        "图标的 base64 数据不合法: {}" => "Invalid base64 data in the icon: {}",
+
+        // 字段名
+        "Java版" => "Java",
+        "基岩版" => "Bedrock",
+        "地址" => "Address",
+        "在线玩家" => "Players",
+        "延迟" => "Latency",
+        "地图" => "Map",
+        "游戏模式" => "Game mode",
+        "服务端" => "Software",
+        "插件" => "Plugins",
+        "玩家列表" => "Player list",
+        "图标" => "Icon",
+        "图片" => "Image",
+        "提示" => "Hint",
+        "监视" => "Watch",
+        "人数变化" => "Trend",
+        "生存" => "Survival",
+        "创造" => "Creative",
+        "硬核" => "Hardcore",
+        "旁观" => "Spectator",
+        "冒险" => "Adventure",
+
+        // 输出
+        "域名解析失败" => "DNS lookup failed",
+        "无法连接到服务器" => "Could not connect to the server",
+        "服务器没有响应" => "The server did not respond",
+        "服务器响应异常" => "Unexpected server response",
+        "获取失败: {}" => "Failed: {}",
+        "查询失败: {}" => "Query failed: {}",
+        "两个版本的 Motd 或人数一致，可能是通过 Geyser 互通的同一服务器" => {
+            "Both editions report the same MOTD or players, likely one server bridged by Geyser"
+        }
+        "已保存到 {}" => "Saved to {}",
+        "{} 个字符超出内置字体的范围，无法绘制图片，请使用文本输出或 --export-motd" => {
+            "{} characters are outside the built-in font, so no image was drawn; use the text output or --export-motd instead"
+        }
+        "无法写入 {}: {}" => "Could not write {}: {}",
+        "基岩版服务器不提供图标" => "Bedrock servers do not provide an icon",
+        "该服务器没有设置图标" => "This server has no icon",
+        "图标数据损坏，{}" => "The icon data is corrupted, {}",
+        "图标缩放失败: {}" => "Could not resize the icon: {}",
+        "请调大控制台窗口的大小" => "Please enlarge the terminal window",
+        "控制台过小，请调大控制台窗口的大小" => {
+            "The terminal is too small, please enlarge it"
+        }
+        "图片输出失败" => "Could not display the image",
+        "图片解码失败" => "Could not decode the image",
+        "半块字符不使用转义序列" => "Half blocks do not use escape sequences",
+        "无法读取服务器列表" => "Could not read the server list",
+        "第 {} 行的端口号不合法" => "Invalid port on line {}",
+        "版本" => "Edition",
+        "游戏版本" => "Version",
+        "玩家" => "Players",
+        "失败" => "Failed",
+        "终端操作失败" => "Terminal operation failed",
+        "第 {} 次查询 · 每 {} 秒刷新 · 按 q 退出" => {
+            "Poll {} · every {}s · press q to quit"
+        }
+        "监听" => "Listening",
+        "服务器" => "Servers",
+        "无法监听 {}" => "Cannot listen on {}",
+        "缓存时间" => "Cache TTL",
+        "历史记录写入失败" => "Failed to write the history",
+        "历史记录读取失败" => "Failed to read the history",
+        "第 {} 行的格式不正确" => "Line {} is malformed",
+        "无法读取 {}: {}" => "Cannot read {}: {}",
+        "在线" => "Online",
+        "离线" => "Offline",
+        "没有符合条件的记录" => "No matching records",
+        "时间范围" => "Range",
+        "{} 条记录" => "{} records",
+        "在线率" => "Uptime",
+        "在线时间轴" => "Timeline",
+        "最高人数" => "Peak",
+        "平均延迟" => "Latency",
+        "人数趋势" => "Players",
+        "版本变更" => "Versions",
+        "无，始终为 {}" => "No changes, always {}",
+        "时段分布" => "By hour",
+
+        // 参数
+        "您的输入有误" => "Invalid input",
+        "使用 {} -h 查看帮助" => "Run {} -h for help",
+        "未知的选项" => "Unknown option",
+        "该选项需要一个值" => "This option requires a value",
+        "请输入服务器地址" => "Please enter a server address",
+        "您的输入参数过多" => "Too many arguments",
+        "这是一个不合法的端口号" => "This is not a valid port",
+        "IPv6 地址应写为 [地址]:端口" => "Write IPv6 addresses as [address]:port",
+        "批量模式下不能再指定地址" => "An address cannot be given in batch mode",
+        "--java、--bedrock 与 --both 只能指定一个" => {
+            "Only one of --java, --bedrock and --both can be given"
+        }
+        "图标边长应为 1 - 4096 之间的整数" => {
+            "The icon size must be an integer from 1 to 4096"
+        }
+        "导出文件的扩展名应为 .html 或 .svg" => {
+            "The export file must end with .html or .svg"
+        }
+        "可选值为 auto、kitty、iterm2、sixel、blocks" => {
+            "Possible values: auto, kitty, iterm2, sixel, blocks"
+        }
+        "可选值为 auto、always、never、256、16" => {
+            "Possible values: auto, always, never, 256, 16"
+        }
+        "可选值为 zh-CN、en-US" => "Possible values: zh-CN, en-US",
+        "并发数应为正整数" => "The concurrency must be a positive integer",
+        "DNS 服务器应为 IP 地址" => "The DNS server must be an IP address",
+        "查询 SRV 记录的 DNS 服务器，默认读取 /etc/resolv.conf，Windows 上需指定才会查询 SRV" => {
+            "DNS server for SRV lookups, read from /etc/resolv.conf by default; required on Windows for SRV lookups"
+        }
+        "超时时间应为正数" => "The timeout must be a positive number",
+        "刷新间隔应为正整数" => "The interval must be a positive integer",
+        "--listen 只能用于 exporter 与 serve 模式" => {
+            "--listen only works in exporter and serve mode"
+        }
+        "--cache-ttl 只能用于 serve 模式" => "--cache-ttl only works in serve mode",
+        "缓存时间应为非负整数" => "The cache TTL must be a non-negative integer",
+        "serve 模式不需要指定地址" => "serve mode takes no address",
+        "serve 模式不能使用 --batch" => "serve mode cannot use --batch",
+        "--store 只能用于 record 与 history 模式" => {
+            "--store only works in record and history mode"
+        }
+        "历史记录文件的扩展名应为 .csv、.db 或 .sqlite" => {
+            "The history file must end with .csv, .db or .sqlite"
+        }
+        "该选项只能用于 history 模式" => "This option only works in history mode",
+        "时间应为 7d、12h 等时长，或 2024-01-01 [12:00] 形式的日期" => {
+            "Use a duration like 7d or 12h, or a date like 2024-01-01 [12:00]"
+        }
+        "record 与 history 模式不能使用 --batch" => {
+            "record and history mode cannot use --batch"
+        }
+        "请使用 --store 指定历史记录文件" => "Please give the history file with --store",
+        "该选项只能用于 check 模式" => "This option only works in check mode",
+        "延迟阈值应为以毫秒为单位的整数" => {
+            "The latency threshold must be an integer in milliseconds"
+        }
+        "比例应为 0 - 1 之间的小数或百分数" => {
+            "The ratio must be a decimal from 0 to 1 or a percentage"
+        }
+        "这是一个不合法的正则表达式" => "This is not a valid regular expression",
+        "警告阈值不能大于严重阈值" => {
+            "The warning threshold cannot be greater than the critical threshold"
+        }
+        "--icon-size 需要与 --save-icon 一起使用" => "--icon-size requires --save-icon",
+        "--both 与导出类选项只能用于查询单个服务器" => {
+            "--both and the export options only work with a single server"
+        }
+
+        // 配置文件
+        "配置文件有误" => "Invalid config file",
+        "配置项 timeout 应为正数" => "timeout must be a positive number",
+        "配置项 color 的可选值为 auto、always、never、256、16" => {
+            "color must be one of auto, always, never, 256, 16"
+        }
+        "配置项 lang 的可选值为 zh-CN、en-US" => "lang must be one of zh-CN, en-US",
+        "别名 {} 的地址不合法" => "The address of alias {} is invalid",
+
+        // 帮助
+        "配置文件" => "Config",
+        "跨平台的 Minecraft 服务器 Motd 测试工具" => {
+            "Cross-platform Minecraft server MOTD tester"
+        }
+        "食用方法" => "Usage",
+        "选项" => "Options",
+        "<IP> <端口>" => "<IP> <port>",
+        "<IP:端口>" => "<IP:port>",
+        "--batch <文件>" => "--batch <file>",
+        "watch <IP:端口>" => "watch <IP:port>",
+        "exporter <IP:端口>..." => "exporter <IP:port>...",
+        "--listen <地址>" => "--listen <addr>",
+        "--cache-ttl <秒>" => "--cache-ttl <secs>",
+        "check <IP:端口>" => "check <IP:port>",
+        "record <IP:端口> --store <文件>" => "record <IP:port> --store <file>",
+        "history [IP:端口] --store <文件>" => "history [IP:port] --store <file>",
+        "--store <文件>" => "--store <file>",
+        "--since <时间>" => "--since <time>",
+        "--until <时间>" => "--until <time>",
+        "--warning-latency <毫秒>" => "--warning-latency <ms>",
+        "--critical-latency <毫秒>" => "--critical-latency <ms>",
+        "--warning-fill <比例>" => "--warning-fill <ratio>",
+        "--critical-fill <比例>" => "--critical-fill <ratio>",
+        "--expect-version <正则>" => "--expect-version <regex>",
+        "--timeout <秒>" => "--timeout <secs>",
+        "--save-icon <路径>" => "--save-icon <path>",
+        "--export-motd <路径>" => "--export-motd <path>",
+        "--render-png <路径>" => "--render-png <path>",
+        "--color <模式>" => "--color <mode>",
+        "--interval <秒>" => "--interval <secs>",
+        "--lang <语言>" => "--lang <lang>",
+        "以 JSON 格式输出" => "Print as JSON",
+        "通过 Query 协议获取完整玩家列表和插件" => {
+            "Fetch the full player list and plugins via Query"
+        }
+        "查询的超时时间，默认为 20 秒，exporter 默认为 8 秒" => {
+            "Query timeout, 20 seconds by default and 8 seconds for exporter"
+        }
+        "只查询 Java 版" => "Only query Java Edition",
+        "只查询基岩版" => "Only query Bedrock Edition",
+        "同时显示两个版本的结果" => "Show the results of both editions",
+        "将服务器图标保存为 PNG 文件" => "Save the server icon as PNG",
+        "保存前将图标缩放为 n×n 像素" => "Resize the saved icon to n×n pixels",
+        "将 Motd 导出为 HTML 或 SVG 文件" => "Export the MOTD as HTML or SVG",
+        "仿照游戏内的服务器列表绘制为 PNG 图片" => {
+            "Render a server list entry as PNG"
+        }
+        "显示图标的方式: auto、kitty、iterm2、sixel、blocks" => {
+            "Icon display: auto, kitty, iterm2, sixel, blocks"
+        }
+        "颜色: auto、always、never、256、16" => "Colors: auto, always, never, 256, 16",
+        "从文件批量查询，为 - 时读取标准输入" => {
+            "Query servers from a file, - for stdin"
+        }
+        "批量查询与 serve 的并发数，默认为 16" => {
+            "Batch and serve concurrency, 16 by default"
+        }
+        "刷新间隔: watch 默认 5 秒，exporter 默认随抓取查询，record 默认只记录一次" => {
+            "Interval: 5s for watch, on scrape for exporter, once for record"
+        }
+        "exporter 与 serve 监听的地址，默认为 0.0.0.0:9565 与 127.0.0.1:8080" => {
+            "Listen address, 0.0.0.0:9565 for exporter, 127.0.0.1:8080 for serve"
+        }
+        "历史记录文件: .csv 或 .db (SQLite)" => "History file: .csv or .db (SQLite)",
+        "history 的起始时间，如 7d、2024-01-01" => "history start, e.g. 7d or 2024-01-01",
+        "history 的结束时间，默认为现在" => "history end, now by default",
+        "check 的延迟阈值，超过时为 WARNING" => "check latency for WARNING",
+        "check 的延迟阈值，超过时为 CRITICAL" => "check latency for CRITICAL",
+        "check 的人数占比阈值，如 0.9 或 90%" => {
+            "check player fill for WARNING, e.g. 0.9 or 90%"
+        }
+        "check 的人数占比阈值，超过时为 CRITICAL" => "check player fill for CRITICAL",
+        "check 时版本不匹配则为 WARNING" => {
+            "check: WARNING when the version does not match"
+        }
+        "serve 缓存查询结果的时间，默认为 30 秒" => {
+            "How long serve caches results, 30 seconds by default"
+        }
+        "界面语言: zh-CN、en-US，默认跟随系统" => {
+            "Language: zh-CN, en-US, system default"
+        }
+
+        _ => return None,
+    })
+}
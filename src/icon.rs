@@ -0,0 +1,28 @@
+use image::{imageops::FilterType, load_from_memory, ImageFormat};
+use motd::{tr, Edition, ServerStatus};
+
+use std::{fs, io::Cursor, path::Path};
+
+/// 将服务器图标写入 `path`，指定 `size` 时先缩放为 `size`×`size`，否则原样写入
+pub fn save_icon(status: &ServerStatus, path: &Path, size: Option<u32>) -> Result<(), String> {
+    let png = match status.favicon_png() {
+        None if status.edition == Edition::Bedrock => {
+            return Err(tr!("基岩版服务器不提供图标").to_string())
+        }
+        None => return Err(tr!("该服务器没有设置图标").to_string()),
+        Some(png) => png.map_err(|e| tr!("图标数据损坏，{}", e))?,
+    };
+    let data = match size {
+        None => png,
+        Some(size) => {
+            let image = load_from_memory(&png).map_err(|e| tr!("图标数据损坏，{}", e))?;
+            let mut buffer = Cursor::new(Vec::new());
+            image
+                .resize_exact(size, size, FilterType::Lanczos3)
+                .write_to(&mut buffer, ImageFormat::Png)
+                .map_err(|e| tr!("图标缩放失败: {}", e))?;
+            buffer.into_inner()
+        }
+    };
+    fs::write(path, data).map_err(|e| tr!("无法写入 {}: {}", path.display(), e))
+}
@@ -0,0 +1,119 @@
+use crate::{net::invalid_data, tr};
+
+use serde_json::Value;
+
+use std::{
+    io::{self, Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// 握手时使用的协议版本，-1 表示由服务器决定
+const HANDSHAKE_PROTOCOL: i32 = -1;
+const MAX_PACKET_LEN: usize = 1 << 21;
+
+pub struct JavaPing {
+    /// 服务器返回的完整状态 JSON
+    pub raw: Value,
+    /// 由 ping/pong 测得的往返延迟，服务器不响应 ping 时为空
+    pub latency: Option<Duration>,
+}
+
+/// 在已建立的连接上按 Server List Ping 协议依次完成握手、状态请求和 ping/pong
+pub fn ping(mut stream: TcpStream, hostname: &str, timeout: Duration) -> io::Result<JavaPing> {
+    let address = stream.peer_addr()?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    stream.set_nodelay(true)?;
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, HANDSHAKE_PROTOCOL);
+    write_string(&mut handshake, hostname);
+    handshake.extend_from_slice(&address.port().to_be_bytes());
+    write_varint(&mut handshake, 1);
+    send_packet(&mut stream, &handshake)?;
+    send_packet(&mut stream, &[0x00])?;
+
+    let response = read_packet(&mut stream)?;
+    let mut cursor = response.as_slice();
+    if read_varint(&mut cursor)? != 0x00 {
+        return Err(invalid_data(tr!("状态响应的数据包 ID 不正确")));
+    }
+    let len = read_varint(&mut cursor)? as usize;
+    let json = cursor
+        .get(..len)
+        .ok_or_else(|| invalid_data(tr!("状态响应不完整")))?;
+    let raw: Value = serde_json::from_slice(json).map_err(|e| invalid_data(&e.to_string()))?;
+
+    Ok(JavaPing {
+        raw,
+        latency: measure_latency(&mut stream).ok(),
+    })
+}
+
+fn measure_latency(stream: &mut TcpStream) -> io::Result<Duration> {
+    let payload = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as i64)
+        .unwrap_or_default();
+    let mut ping = vec![0x01];
+    ping.extend_from_slice(&payload.to_be_bytes());
+
+    let start = Instant::now();
+    send_packet(stream, &ping)?;
+    let pong = read_packet(stream)?;
+    let latency = start.elapsed();
+
+    if pong.len() != 9 || pong[0] != 0x01 || pong[1..] != payload.to_be_bytes() {
+        return Err(invalid_data(tr!("pong 数据包不正确")));
+    }
+    Ok(latency)
+}
+
+fn send_packet(stream: &mut TcpStream, data: &[u8]) -> io::Result<()> {
+    let mut packet = Vec::with_capacity(data.len() + 5);
+    write_varint(&mut packet, data.len() as i32);
+    packet.extend_from_slice(data);
+    stream.write_all(&packet)
+}
+
+fn read_packet(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let len = read_varint(stream)?;
+    if len <= 0 || len as usize > MAX_PACKET_LEN {
+        return Err(invalid_data(tr!("数据包长度不合法")));
+    }
+    let mut data = vec![0; len as usize];
+    stream.read_exact(&mut data)?;
+    Ok(data)
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7f == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+    write_varint(buf, value.len() as i32);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+fn read_varint(reader: &mut impl Read) -> io::Result<i32> {
+    let mut value: u32 = 0;
+    for i in 0..5 {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u32) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(invalid_data(tr!("VarInt 过长")))
+}
@@ -0,0 +1,169 @@
+use motd::{BothStatus, Edition, FullStat, GameMode, QueryError, ServerStatus, SrvRecord};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize)]
+struct StatusDocument<'a> {
+    online: bool,
+    edition: &'static str,
+    version: &'a str,
+    protocol: i32,
+    motd: MotdDocument,
+    players: PlayersDocument<'a>,
+    map: Option<&'a str>,
+    game_mode: Option<&'static str>,
+    favicon: bool,
+    latency_ms: Option<u64>,
+    address: AddressDocument<'a>,
+    query: Option<QueryDocument<'a>>,
+    raw: Option<&'a Value>,
+}
+
+#[derive(Serialize)]
+struct MotdDocument {
+    raw: String,
+    plain: String,
+}
+
+#[derive(Serialize)]
+struct PlayersDocument<'a> {
+    online: u32,
+    max: u32,
+    sample: &'a [String],
+}
+
+#[derive(Serialize)]
+struct AddressDocument<'a> {
+    host: &'a str,
+    ip: String,
+    port: u16,
+    srv: Option<SrvDocument<'a>>,
+}
+
+#[derive(Serialize)]
+struct SrvDocument<'a> {
+    target: &'a str,
+    port: u16,
+}
+
+/// `--query` 的结果，失败时只有 `error`
+#[derive(Serialize, Default)]
+struct QueryDocument<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    software: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plugins: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    game_type: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    players: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct ErrorDocument<'a> {
+    online: bool,
+    host: &'a str,
+    error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    kind: &'static str,
+    message: String,
+}
+
+pub fn status_to_json(host: &str, status: &ServerStatus) -> Value {
+    let document = StatusDocument {
+        online: true,
+        edition: match status.edition {
+            Edition::Java => "java",
+            Edition::Bedrock => "bedrock",
+        },
+        version: &status.version,
+        protocol: status.protocol,
+        motd: MotdDocument {
+            raw: status.motd.clone(),
+            plain: status.motd_plain(),
+        },
+        players: PlayersDocument {
+            online: status.players_online,
+            max: status.players_max,
+            sample: &status.players,
+        },
+        map: status.map.as_deref(),
+        game_mode: status.game_mode.map(|game_mode| match game_mode {
+            GameMode::Survival => "survival",
+            GameMode::Creative => "creative",
+            GameMode::Hardcore => "hardcore",
+            GameMode::Spectator => "spectator",
+            GameMode::Adventure => "adventure",
+        }),
+        favicon: status.favicon.is_some(),
+        latency_ms: status.latency.map(|latency| latency.as_millis() as u64),
+        address: AddressDocument {
+            host,
+            ip: status.address.ip().to_string(),
+            port: status.address.port(),
+            srv: status
+                .srv
+                .as_ref()
+                .map(|SrvRecord { target, port, .. }| SrvDocument {
+                    target,
+                    port: *port,
+                }),
+        },
+        query: status.full_stat.as_ref().map(|full_stat| match full_stat {
+            Ok(FullStat {
+                software,
+                plugins,
+                game_type,
+                players,
+                ..
+            }) => QueryDocument {
+                software: software.as_deref(),
+                plugins: Some(plugins),
+                game_type: game_type.as_deref(),
+                players: Some(players),
+                ..Default::default()
+            },
+            Err(e) => QueryDocument {
+                error: Some(e),
+                ..Default::default()
+            },
+        }),
+        raw: status.raw.as_ref(),
+    };
+    serde_json::to_value(&document).unwrap()
+}
+
+pub fn error_to_json(host: &str, error: &QueryError) -> Value {
+    let document = ErrorDocument {
+        online: false,
+        host,
+        error: ErrorDetail {
+            kind: match error {
+                QueryError::Resolve(_) => "resolve",
+                QueryError::Connect(_) => "connect",
+                QueryError::Timeout => "timeout",
+                QueryError::Protocol(_) => "protocol",
+            },
+            message: error.to_string(),
+        },
+    };
+    serde_json::to_value(&document).unwrap()
+}
+
+/// `--both` 的输出，两个版本分别为状态或错误文档
+pub fn both_to_json(host: &str, result: &BothStatus) -> Value {
+    let document = |status: &Result<ServerStatus, QueryError>| match status {
+        Ok(status) => status_to_json(host, status),
+        Err(e) => error_to_json(host, e),
+    };
+    serde_json::json!({
+        "java": document(&result.java),
+        "bedrock": document(&result.bedrock),
+        "likely_geyser": result.likely_geyser(),
+    })
+}
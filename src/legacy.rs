@@ -0,0 +1,183 @@
+use crate::{net::invalid_data, tr};
+
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream},
+    time::{Duration, Instant},
+};
+
+/// 1.6 客户端在 ping 数据包中声明的协议版本
+const LEGACY_PROTOCOL: u8 = 78;
+
+pub struct LegacyPing {
+    /// Beta 1.8 - 1.3 的服务器不返回协议版本
+    pub protocol: i32,
+    pub version: String,
+    pub motd: String,
+    pub players_online: u32,
+    pub players_max: u32,
+    pub latency: Duration,
+}
+
+/// 以 1.6 客户端的格式发送 legacy ping
+///
+/// 1.4 - 1.5 的服务器会忽略 `MC|PingHost` 部分，Beta 1.8 - 1.3 的服务器只读取首字节，
+/// 因此一次请求即可兼容所有旧版本，再按响应的格式分别解析
+pub fn ping(address: SocketAddr, hostname: &str, timeout: Duration) -> io::Result<LegacyPing> {
+    let mut stream = TcpStream::connect_timeout(&address, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut payload = vec![LEGACY_PROTOCOL];
+    write_utf16(&mut payload, hostname);
+    payload.extend_from_slice(&(address.port() as i32).to_be_bytes());
+
+    let mut request = vec![0xfe, 0x01, 0xfa];
+    write_utf16(&mut request, "MC|PingHost");
+    request.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    request.extend_from_slice(&payload);
+
+    let start = Instant::now();
+    stream.write_all(&request)?;
+
+    let mut header = [0u8; 3];
+    stream.read_exact(&mut header)?;
+    let latency = start.elapsed();
+    if header[0] != 0xff {
+        return Err(invalid_data(tr!("legacy ping 响应的数据包 ID 不正确")));
+    }
+    let len = u16::from_be_bytes([header[1], header[2]]) as usize;
+    let mut data = vec![0u8; len * 2];
+    stream.read_exact(&mut data)?;
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+        .collect();
+    let response = String::from_utf16_lossy(&units);
+
+    parse_response(&response, latency)
+}
+
+fn parse_response(response: &str, latency: Duration) -> io::Result<LegacyPing> {
+    // 1.4 - 1.6: §1\0协议版本\0游戏版本\0Motd\0在线人数\0最大人数
+    if let Some(fields) = response.strip_prefix("§1\0") {
+        let fields: Vec<&str> = fields.split('\0').collect();
+        let [protocol, version, motd, online, max] = fields[..] else {
+            return Err(invalid_data(tr!("legacy ping 响应的字段数量不正确")));
+        };
+        return Ok(LegacyPing {
+            protocol: protocol.parse().unwrap_or(-1),
+            version: version.to_string(),
+            motd: motd.to_string(),
+            players_online: online.parse().unwrap_or_default(),
+            players_max: max.parse().unwrap_or_default(),
+            latency,
+        });
+    }
+
+    // Beta 1.8 - 1.3: Motd§在线人数§最大人数，从右侧拆分以保留 Motd 中的 §
+    let mut fields = response.rsplitn(3, '§');
+    let (Some(max), Some(online), Some(motd)) = (fields.next(), fields.next(), fields.next())
+    else {
+        return Err(invalid_data(tr!("无法识别的 legacy ping 响应")));
+    };
+    Ok(LegacyPing {
+        protocol: -1,
+        version: "Beta 1.8 - 1.3".to_string(),
+        motd: motd.to_string(),
+        players_online: online
+            .parse()
+            .map_err(|_| invalid_data(tr!("无法识别的 legacy ping 响应")))?,
+        players_max: max
+            .parse()
+            .map_err(|_| invalid_data(tr!("无法识别的 legacy ping 响应")))?,
+        latency,
+    })
+}
+
+fn write_utf16(buf: &mut Vec<u8>, value: &str) {
+    let units: Vec<u16> = value.encode_utf16().collect();
+    buf.extend_from_slice(&(units.len() as u16).to_be_bytes());
+    for unit in units {
+        buf.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{net::TcpListener, thread};
+
+    /// 1.6.4 原版服务端的响应
+    const RESPONSE_1_6: &str = "§1\x0078\x001.6.4\x00A Minecraft Server\x003\x0020";
+    /// Beta 1.8.1 原版服务端的响应
+    const RESPONSE_BETA: &str = "§aA §lMinecraft§r Server§3§20";
+
+    /// 以 `0xff` 数据包发送 `response`，只发送前 `limit` 个字节
+    fn stub_server(response: &str, limit: usize) -> SocketAddr {
+        let units: Vec<u16> = response.encode_utf16().collect();
+        let mut packet = vec![0xff];
+        packet.extend_from_slice(&(units.len() as u16).to_be_bytes());
+        for unit in units {
+            packet.extend_from_slice(&unit.to_be_bytes());
+        }
+        packet.truncate(limit);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 256];
+            let _ = stream.read(&mut request);
+            stream.write_all(&packet).unwrap();
+        });
+        address
+    }
+
+    #[test]
+    fn parse_1_6() {
+        let ping = parse_response(RESPONSE_1_6, Duration::ZERO).unwrap();
+        assert_eq!(ping.protocol, 78);
+        assert_eq!(ping.version, "1.6.4");
+        assert_eq!(ping.motd, "A Minecraft Server");
+        assert_eq!((ping.players_online, ping.players_max), (3, 20));
+    }
+
+    #[test]
+    fn parse_beta_keeps_formatting_in_motd() {
+        let ping = parse_response(RESPONSE_BETA, Duration::ZERO).unwrap();
+        assert_eq!(ping.protocol, -1);
+        assert_eq!(ping.motd, "§aA §lMinecraft§r Server");
+        assert_eq!((ping.players_online, ping.players_max), (3, 20));
+    }
+
+    #[test]
+    fn parse_truncated_response() {
+        // 截断在最后一个字段中时无法从文本本身判断，由数据包的长度保证完整
+        for (response, separator) in [(RESPONSE_1_6, '\0'), (RESPONSE_BETA, '§')] {
+            let last = response.rfind(separator).unwrap();
+            for (end, _) in response[..last].char_indices() {
+                let truncated = &response[..end];
+                assert!(
+                    parse_response(truncated, Duration::ZERO).is_err(),
+                    "{:?}",
+                    truncated
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ping_stub_server() {
+        let address = stub_server(RESPONSE_1_6, usize::MAX);
+        let ping = ping(address, "localhost", Duration::from_secs(2)).unwrap();
+        assert_eq!(ping.version, "1.6.4");
+    }
+
+    #[test]
+    fn ping_truncated_packet() {
+        let address = stub_server(RESPONSE_1_6, 20);
+        assert!(ping(address, "localhost", Duration::from_secs(2)).is_err());
+    }
+}
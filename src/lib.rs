@@ -0,0 +1,24 @@
+//! Minecraft 服务器 Motd 查询库
+//!
+//! 无需指定版本，同时尝试 Java 版与基岩版协议，返回统一的 [`ServerStatus`]
+
+mod bedrock;
+mod error;
+pub mod export;
+mod eyeballs;
+pub mod formatting;
+mod gamespy;
+pub mod i18n;
+mod java;
+mod legacy;
+mod net;
+mod query;
+mod srv;
+mod status;
+pub mod text;
+
+pub use error::QueryError;
+pub use gamespy::FullStat;
+pub use query::{query, query_both, server_name, QueryOptions};
+pub use srv::{lookup_minecraft_srv, SrvRecord};
+pub use status::{BothStatus, Edition, GameMode, ServerStatus};
@@ -0,0 +1,23 @@
+use std::{
+    io,
+    net::{SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+/// 绑定与 `peer` 相同地址族的临时端口，连接到 `peer` 并设置读取超时
+pub fn connect_udp(peer: SocketAddr, timeout: Duration) -> io::Result<UdpSocket> {
+    let bind: SocketAddr = if peer.is_ipv4() {
+        "0.0.0.0:0".parse().unwrap()
+    } else {
+        "[::]:0".parse().unwrap()
+    };
+    let socket = UdpSocket::bind(bind)?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(peer)?;
+    Ok(socket)
+}
+
+/// 收到的数据无法识别
+pub fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
@@ -0,0 +1,316 @@
+use crate::{
+    bedrock,
+    error::QueryError,
+    eyeballs, gamespy, java, legacy,
+    srv::{lookup_with_timeout, SrvRecord},
+    status::{BothStatus, Edition, ServerStatus},
+    tr,
+};
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const JAVA_DEFAULT_PORT: u16 = 25565;
+const BEDROCK_DEFAULT_PORT: u16 = 19132;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(20);
+const FULL_STAT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 新版握手最多占用的时间，1.7 以前的服务器可能不响应新版握手，需要留出 legacy ping 的时间
+const MODERN_PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// SRV 查询最多占用的时间，DNS 服务器无响应时仍留出连接服务器的时间
+const SRV_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct QueryOptions {
+    /// 为空时 Java 版使用 25565，基岩版使用 19132
+    pub port: Option<u16>,
+    /// 查询 SRV 记录使用的 DNS 服务器，为空时使用系统配置
+    pub nameserver: Option<SocketAddr>,
+    /// Java 版额外通过 GameSpy4 协议获取完整玩家列表和插件等信息
+    pub full_stat: bool,
+    /// 整次查询的时限，默认为 20 秒
+    pub timeout: Duration,
+    /// 只查询指定的版本，为空时两个版本同时查询
+    pub edition: Option<Edition>,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        QueryOptions {
+            port: None,
+            nameserver: None,
+            full_stat: false,
+            timeout: DEFAULT_TIMEOUT,
+            edition: None,
+        }
+    }
+}
+
+/// 查询线程共享的截止时间，查询结束后其余线程在下一个阶段开始前退出
+#[derive(Clone)]
+struct Deadline {
+    at: Instant,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Deadline {
+    /// 当前阶段可用的剩余时间
+    fn remaining(&self) -> Result<Duration, QueryError> {
+        let remaining = self.at.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || self.cancelled.load(Ordering::Relaxed) {
+            return Err(QueryError::Timeout);
+        }
+        Ok(remaining)
+    }
+}
+
+/// 同时以 Java 版和基岩版协议查询，返回最先成功的结果
+///
+/// 指定了 [`QueryOptions::edition`] 时只查询该版本。IPv6 地址可以带方括号，例如 `[::1]`
+pub fn query(addr: &str, options: &QueryOptions) -> Result<ServerStatus, QueryError> {
+    let editions = match options.edition {
+        Some(edition) => vec![edition],
+        None => vec![Edition::Java, Edition::Bedrock],
+    };
+    let (deadline, rx) = spawn_queries(addr, options, &editions);
+
+    let mut errors = Vec::new();
+    for _ in 0..editions.len() {
+        let timeout = deadline.at.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(timeout) {
+            Ok((_, Ok(status))) => {
+                deadline.cancelled.store(true, Ordering::Relaxed);
+                return Ok(status);
+            }
+            Ok((_, Err(e))) => errors.push(e),
+            Err(_) => {
+                errors.push(QueryError::Timeout);
+                break;
+            }
+        }
+    }
+    deadline.cancelled.store(true, Ordering::Relaxed);
+    // 两边都失败时报告最具体的错误，例如一边被拒绝、另一边超时时报告被拒绝
+    Err(errors.into_iter().max_by_key(QueryError::rank).unwrap())
+}
+
+/// 等待两个版本的查询都结束，适用于同时提供两个版本入口的服务器
+pub fn query_both(addr: &str, options: &QueryOptions) -> BothStatus {
+    let (deadline, rx) = spawn_queries(addr, options, &[Edition::Java, Edition::Bedrock]);
+
+    let (mut java, mut bedrock) = (Err(QueryError::Timeout), Err(QueryError::Timeout));
+    for _ in 0..2 {
+        let timeout = deadline.at.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(timeout) {
+            Ok((Edition::Java, result)) => java = result,
+            Ok((Edition::Bedrock, result)) => bedrock = result,
+            Err(_) => break,
+        }
+    }
+    deadline.cancelled.store(true, Ordering::Relaxed);
+    BothStatus { java, bedrock }
+}
+
+type QueryResult = (Edition, Result<ServerStatus, QueryError>);
+
+/// 为每个版本启动一个查询线程，结果附带版本发送到同一个通道
+fn spawn_queries(
+    addr: &str,
+    options: &QueryOptions,
+    editions: &[Edition],
+) -> (Deadline, mpsc::Receiver<QueryResult>) {
+    let addr = addr
+        .strip_prefix('[')
+        .and_then(|addr| addr.strip_suffix(']'))
+        .unwrap_or(addr);
+    let deadline = Deadline {
+        at: Instant::now() + options.timeout,
+        cancelled: Arc::new(AtomicBool::new(false)),
+    };
+    let (tx, rx) = mpsc::channel();
+    for &edition in editions {
+        let (tx, host, options, deadline) = (
+            tx.clone(),
+            addr.to_string(),
+            options.clone(),
+            deadline.clone(),
+        );
+        thread::spawn(move || {
+            let result = match edition {
+                Edition::Java => query_java(&host, &options, &deadline),
+                Edition::Bedrock => query_bedrock(&host, options.port, &deadline),
+            };
+            let _ = tx.send((edition, result));
+        });
+    }
+    (deadline, rx)
+}
+
+fn query_java(
+    host: &str,
+    options: &QueryOptions,
+    deadline: &Deadline,
+) -> Result<ServerStatus, QueryError> {
+    // 与原版客户端一致，仅在未指定端口且地址为域名时查询 SRV 记录
+    let srv = if options.port.is_none() && host.parse::<IpAddr>().is_err() {
+        lookup_with_timeout(
+            host,
+            options.nameserver,
+            deadline.remaining()?.min(SRV_TIMEOUT),
+        )
+        .ok()
+        .flatten()
+    } else {
+        None
+    };
+    let candidates = match &srv {
+        Some(SrvRecord { target, port, .. }) => resolve(target, *port)?,
+        None => resolve(host, options.port.unwrap_or(JAVA_DEFAULT_PORT))?,
+    };
+
+    let (address, stream) =
+        eyeballs::race(&candidates, deadline.remaining()?, |address, timeout| {
+            TcpStream::connect_timeout(&address, timeout)
+        })
+        .map_err(QueryError::from_io)?;
+    let mut status = ping_java(address, stream, host, deadline)?;
+    status.srv = srv;
+    if options.full_stat {
+        let full_stat = match deadline.remaining() {
+            Ok(remaining) => gamespy::full_stat(address, remaining.min(FULL_STAT_TIMEOUT))
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Ok(stat) = &full_stat {
+            if status.map.is_none() {
+                status.map = stat.map.clone();
+            }
+            if stat.players.len() >= status.players.len() {
+                status.players = stat.players.clone();
+            }
+        }
+        status.full_stat = Some(full_stat);
+    }
+    Ok(status)
+}
+
+/// 先以新版握手查询，失败或超时时再尝试 legacy ping
+fn ping_java(
+    address: SocketAddr,
+    stream: TcpStream,
+    host: &str,
+    deadline: &Deadline,
+) -> Result<ServerStatus, QueryError> {
+    let timeout = (deadline.remaining()? / 2).min(MODERN_PING_TIMEOUT);
+    match java::ping(stream, host, timeout) {
+        Ok(ping) => Ok(ServerStatus::from_java(ping, address)),
+        Err(e) => match QueryError::from_io(e) {
+            // 1.7 以前的服务器不认识新版握手，通常会直接断开连接或一直不响应，此时再尝试 legacy ping
+            e @ (QueryError::Protocol(_) | QueryError::Timeout) => {
+                legacy::ping(address, host, deadline.remaining()?)
+                    .map(|ping| ServerStatus::from_legacy(ping, address))
+                    // legacy ping 也失败时，新版协议的错误更能说明问题
+                    .map_err(|_| e)
+            }
+            e => Err(e),
+        },
+    }
+}
+
+fn query_bedrock(
+    host: &str,
+    port: Option<u16>,
+    deadline: &Deadline,
+) -> Result<ServerStatus, QueryError> {
+    let candidates = resolve(host, port.unwrap_or(BEDROCK_DEFAULT_PORT))?;
+    eyeballs::race(&candidates, deadline.remaining()?, bedrock::ping)
+        .map(|(address, ping)| ServerStatus::from_bedrock(ping, address))
+        .map_err(QueryError::from_io)
+}
+
+/// 显示与记录使用的服务器名称，与输入的地址一致，IPv6 地址带方括号
+pub fn server_name(addr: &str, port: Option<u16>) -> String {
+    let host = if addr.contains(':') {
+        format!("[{}]", addr)
+    } else {
+        addr.to_string()
+    };
+    match port {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+    }
+}
+
+/// 解析出全部地址并按 Happy Eyeballs 的顺序排列
+fn resolve(host: &str, port: u16) -> Result<Vec<SocketAddr>, QueryError> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+    // 同时返回 A 与 AAAA 记录，仅有 AAAA 记录的域名同样可以解析
+    let mut addrs: Vec<SocketAddr> = Vec::new();
+    for addr in (host, port)
+        .to_socket_addrs()
+        .map_err(QueryError::Resolve)?
+    {
+        if !addrs.contains(&addr) {
+            addrs.push(addr);
+        }
+    }
+    if addrs.is_empty() {
+        return Err(QueryError::Resolve(io::Error::new(
+            io::ErrorKind::NotFound,
+            tr!("没有可用的地址"),
+        )));
+    }
+    Ok(eyeballs::interleave(addrs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    #[test]
+    fn silent_server_falls_back_to_legacy() {
+        // 1.6.4 原版服务端的 legacy 响应
+        let units: Vec<u16> = "§1\x0078\x001.6.4\x00A Minecraft Server\x003\x0020"
+            .encode_utf16()
+            .collect();
+        let mut packet = vec![0xff];
+        packet.extend_from_slice(&(units.len() as u16).to_be_bytes());
+        for unit in units {
+            packet.extend_from_slice(&unit.to_be_bytes());
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            // 第一个连接收到新版握手后不作响应，保持连接直到测试结束
+            let (silent, _) = listener.accept().unwrap();
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 256];
+            let _ = stream.read(&mut request);
+            stream.write_all(&packet).unwrap();
+            drop(silent);
+        });
+
+        let deadline = Deadline {
+            at: Instant::now() + Duration::from_secs(4),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        let stream = TcpStream::connect(address).unwrap();
+        let status = ping_java(address, stream, "localhost", &deadline).unwrap();
+        assert_eq!(status.version, "1.6.4");
+    }
+}
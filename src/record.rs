@@ -0,0 +1,85 @@
+use crate::{
+    cli::StoreFormat,
+    render::latency_format,
+    store::{append, Record},
+};
+
+use chrono::Local;
+use colored::Colorize;
+use motd::{query, server_name, tr, QueryOptions};
+
+use std::{
+    path::Path,
+    process::exit,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// 查询并追加一条记录，指定 `interval` 时持续记录直到被中断
+pub fn run(
+    addr: &str,
+    port: Option<u16>,
+    path: &Path,
+    format: StoreFormat,
+    interval: Option<Duration>,
+    options: &QueryOptions,
+) {
+    let options = QueryOptions {
+        port,
+        ..options.clone()
+    };
+    let server = server_name(addr, port);
+    loop {
+        let start = Instant::now();
+        let result = query(addr, &options);
+        let record = Record {
+            time: Local::now(),
+            server: server.clone(),
+            online: result.is_ok(),
+            players_online: result.as_ref().map_or(0, |status| status.players_online),
+            players_max: result.as_ref().map_or(0, |status| status.players_max),
+            latency_ms: result
+                .as_ref()
+                .ok()
+                .and_then(|status| status.latency)
+                .map(|latency| latency.as_millis() as u64),
+            version: result.as_ref().ok().map(|status| status.version.clone()),
+        };
+        if let Err(e) = append(path, format, &record) {
+            println!(
+                "{}\n{}",
+                tr!("历史记录写入失败").bright_red().bold(),
+                e.bright_red()
+            );
+            exit(7);
+        }
+
+        let summary = match &result {
+            Ok(status) => {
+                let mut summary = format!(
+                    "{} {}/{}",
+                    tr!("在线").bright_green(),
+                    status.players_online,
+                    status.players_max
+                );
+                if let Some(latency) = status.latency {
+                    summary.push_str(&format!(" · {}", latency_format(latency)));
+                }
+                summary.push_str(&format!(" · {}", status.version));
+                summary
+            }
+            Err(e) => format!("{} {}", tr!("离线").bright_red(), e),
+        };
+        println!(
+            "{} | {}",
+            record.time.format("%Y-%m-%d %H:%M:%S").to_string().cyan(),
+            summary
+        );
+
+        let Some(interval) = interval else {
+            return;
+        };
+        // 查询耗时计入间隔，保持固定的记录频率
+        thread::sleep(interval.saturating_sub(start.elapsed()));
+    }
+}
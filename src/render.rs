@@ -0,0 +1,522 @@
+use crate::{
+    color::{paint, plain_output},
+    graphics::{image_escape, ImageProtocol},
+};
+
+use motd::{
+    formatting::strip_formatting,
+    i18n::{english, tr},
+    text::{legacy_spans, truncate, Span, Style},
+    tr, Edition, GameMode, ServerStatus,
+};
+
+use colored::{ColoredString, Colorize};
+use image::{imageops::FilterType, load_from_memory, GenericImageView as _, Rgba};
+use unicode_width::UnicodeWidthStr;
+
+use std::{error::Error, time::Duration};
+
+pub fn print_java_motd(status: ServerStatus, protocol: ImageProtocol) {
+    let lines = if plain_output() {
+        plain_lines(&status)
+    } else {
+        java_motd_lines(&status, 0, protocol)
+    };
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+/// `reserved` 为图标下方需要额外保留的行数
+pub fn java_motd_lines(
+    status: &ServerStatus,
+    reserved: u16,
+    protocol: ImageProtocol,
+) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(format!(
+        "{} | {} {}",
+        output_field_format(tr!("Java版")).bright_green(),
+        truncate(&status.version, 30).bright_yellow(),
+        format!("({})", status.protocol).cyan()
+    ));
+    let colored_description = spans_to_colored_string(&status.motd_spans());
+    for (i, line) in colored_description.split('\n').enumerate() {
+        if i == 0 {
+            lines.push(format!(
+                "{} {} {}",
+                output_field_format("Motd").bright_cyan(),
+                "|".bright_cyan().bold(),
+                line
+            ));
+        } else {
+            lines.push(format!(
+                "{} {} {}",
+                output_field_format("").bright_cyan().bold(),
+                "|".bright_cyan().bold(),
+                line
+            ));
+        }
+    }
+    lines.push(format!(
+        "{} | {}",
+        output_field_format(tr!("地址")).bright_cyan(),
+        status.address.to_string().cyan()
+    ));
+    if let Some(srv) = &status.srv {
+        lines.push(format!(
+            "{} | {}:{}",
+            output_field_format("SRV").bright_cyan(),
+            srv.target,
+            srv.port
+        ));
+    };
+    lines.push(format!(
+        "{} | {} / {}",
+        output_field_format(tr!("在线玩家")).bright_cyan(),
+        status.players_online,
+        status.players_max
+    ));
+    if let Some(latency) = status.latency {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("延迟")).bright_cyan(),
+            latency_format(latency)
+        ));
+    };
+
+    if let Some(map) = &status.map {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("地图")).bright_cyan(),
+            to_colored_string(map, status.edition)
+        ));
+    };
+    if let Some(gamemode) = status.game_mode {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("游戏模式")).bright_cyan(),
+            game_mode_name(gamemode)
+        ));
+    };
+    match &status.full_stat {
+        Some(Ok(full_stat)) => {
+            if let Some(software) = &full_stat.software {
+                lines.push(format!(
+                    "{} | {}",
+                    output_field_format(tr!("服务端")).bright_cyan(),
+                    software.bright_yellow()
+                ));
+            }
+            for (i, plugin) in full_stat.plugins.iter().enumerate() {
+                lines.push(format!(
+                    "{} {} {}",
+                    output_field_format(if i == 0 { tr!("插件") } else { "" }).bright_cyan(),
+                    "|".bright_green().bold(),
+                    plugin
+                ));
+            }
+        }
+        Some(Err(e)) => {
+            lines.push(format!(
+                "{} | {}",
+                output_field_format("Query").bright_cyan(),
+                tr!("查询失败: {}", e).bright_red()
+            ));
+        }
+        None => {}
+    }
+    for (i, player) in status.players.iter().enumerate() {
+        if i == 0 {
+            lines.push(format!(
+                "{} {} {}",
+                output_field_format(tr!("玩家列表")).bright_cyan(),
+                "|".bright_green().bold(),
+                to_colored_string(player, status.edition)
+            ));
+        } else {
+            lines.push(format!(
+                "{} {} {}",
+                output_field_format("").bright_cyan(),
+                "|".bright_green().bold(),
+                to_colored_string(player, status.edition)
+            ));
+        }
+    }
+    if let Some(favicon) = status.favicon_png() {
+        match favicon {
+            Ok(image) => {
+                let size = match calc_image_size((
+                    label_width() as u16 + 3,
+                    lines.len() as u16 + 1 + reserved,
+                )) {
+                    Ok(size) => size,
+                    Err(_) => {
+                        lines.push(format!(
+                            "{} {} {}",
+                            output_field_format(tr!("图标")).bright_cyan(),
+                            "|".bright_cyan().bold(),
+                            tr!("请调大控制台窗口的大小").bright_red().bold()
+                        ));
+                        return lines;
+                    }
+                };
+                let image_lines = match protocol {
+                    ImageProtocol::HalfBlocks => img2lines(&image, size as u32),
+                    // 图像协议输出失败时退回半块字符
+                    protocol => image_escape(protocol, &image, size as u16, size as u16 / 2)
+                        .map(|escape| vec![escape])
+                        .or_else(|_| img2lines(&image, size as u32)),
+                };
+                match image_lines {
+                    Ok(image_lines) => {
+                        lines.push(format!("{} |", output_field_format("").bright_cyan()));
+                        for (index, line) in image_lines.into_iter().enumerate() {
+                            if index == 0 {
+                                lines.push(format!(
+                                    "{} {} {}",
+                                    output_field_format(tr!("图标")).bright_cyan(),
+                                    "|".bright_cyan().bold(),
+                                    line
+                                ));
+                            } else {
+                                lines.push(format!(
+                                    "{} {} {}",
+                                    output_field_format("").bright_cyan(),
+                                    "|".bright_cyan().bold(),
+                                    line
+                                ))
+                            };
+                        }
+                    }
+                    Err(_) => {
+                        lines.push(format!(
+                            "{} {} {}",
+                            output_field_format(tr!("图标")).bright_cyan(),
+                            "|".bold(),
+                            tr!("图片输出失败").bright_red().bold()
+                        ));
+                    }
+                };
+            }
+            Err(_) => {
+                lines.push(format!(
+                    "{} {} {}",
+                    output_field_format(tr!("图标")).bright_cyan(),
+                    "|".bold(),
+                    tr!("图片解码失败").bright_red().bold()
+                ));
+            }
+        }
+    }
+    lines
+}
+
+pub fn print_bedrock_motd(status: ServerStatus) {
+    let lines = if plain_output() {
+        plain_lines(&status)
+    } else {
+        bedrock_motd_lines(&status)
+    };
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+/// 不输出颜色时使用的纯文本格式，每行为 `字段: 值`，多值字段逐行重复字段名，不显示图标
+///
+/// 字段名固定为英文，不随界面语言变化，便于脚本按字段名提取
+pub fn plain_lines(status: &ServerStatus) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let edition = match status.edition {
+        Edition::Java => english("Java版"),
+        Edition::Bedrock => english("基岩版"),
+    };
+    lines.push(format!(
+        "{}: {} ({})",
+        edition, status.version, status.protocol
+    ));
+    for line in status.motd_plain().lines() {
+        lines.push(format!("Motd: {}", line));
+    }
+    lines.push(format!("{}: {}", english("地址"), status.address));
+    if let Some(srv) = &status.srv {
+        lines.push(format!("SRV: {}:{}", srv.target, srv.port));
+    }
+    lines.push(format!(
+        "{}: {} / {}",
+        english("在线玩家"),
+        status.players_online,
+        status.players_max
+    ));
+    if let Some(latency) = status.latency {
+        lines.push(format!("{}: {} ms", english("延迟"), latency.as_millis()));
+    }
+    if let Some(map) = &status.map {
+        lines.push(format!("{}: {}", english("地图"), strip_formatting(map)));
+    }
+    if let Some(game_mode) = status.game_mode {
+        lines.push(format!(
+            "{}: {}",
+            english("游戏模式"),
+            game_mode_name(game_mode)
+        ));
+    }
+    match &status.full_stat {
+        Some(Ok(full_stat)) => {
+            if let Some(software) = &full_stat.software {
+                lines.push(format!("{}: {}", english("服务端"), software));
+            }
+            for plugin in &full_stat.plugins {
+                lines.push(format!("{}: {}", english("插件"), plugin));
+            }
+        }
+        Some(Err(e)) => lines.push(format!("Query: {}", tr!("查询失败: {}", e))),
+        None => {}
+    }
+    for player in &status.players {
+        lines.push(format!(
+            "{}: {}",
+            english("玩家列表"),
+            strip_formatting(player)
+        ));
+    }
+    lines
+}
+
+pub fn bedrock_motd_lines(status: &ServerStatus) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(format!(
+        "{} | {} {}",
+        output_field_format(tr!("基岩版")).bright_green(),
+        status.version.bright_yellow(),
+        format!("({})", status.protocol).cyan()
+    ));
+    lines.push(format!(
+        "{} | {}",
+        output_field_format("Motd").bright_cyan(),
+        to_colored_string(&status.motd, status.edition)
+    ));
+    lines.push(format!(
+        "{} | {}",
+        output_field_format(tr!("地址")).bright_cyan(),
+        status.address.to_string().cyan()
+    ));
+    lines.push(format!(
+        "{} | {} / {}",
+        output_field_format(tr!("在线玩家")).bright_cyan(),
+        status.players_online,
+        status.players_max
+    ));
+    if let Some(latency) = status.latency {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("延迟")).bright_cyan(),
+            latency_format(latency)
+        ));
+    };
+    if let Some(map) = &status.map {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("地图")).bright_cyan(),
+            to_colored_string(map, status.edition)
+        ));
+    };
+    if let Some(gamemode) = status.game_mode {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("游戏模式")).bright_cyan(),
+            game_mode_name(gamemode)
+        ));
+    };
+    if let Some((first, rest)) = status.players.split_first() {
+        lines.push(format!(
+            "{} | {}",
+            output_field_format(tr!("玩家列表")).bright_cyan(),
+            first
+        ));
+        for player in rest {
+            lines.push(format!(
+                "{} {} {}",
+                output_field_format("").bright_cyan(),
+                "|".bright_green().bold(),
+                to_colored_string(player, status.edition)
+            ));
+        }
+    };
+    lines
+}
+
+pub fn latency_format(latency: Duration) -> ColoredString {
+    let millis = latency.as_millis();
+    let text = format!("{} ms", millis);
+    match millis {
+        0..=99 => text.bright_green(),
+        100..=299 => text.bright_yellow(),
+        _ => text.bright_red(),
+    }
+}
+
+fn game_mode_name(game_mode: GameMode) -> &'static str {
+    match game_mode {
+        GameMode::Survival => tr!("生存"),
+        GameMode::Creative => tr!("创造"),
+        GameMode::Hardcore => tr!("硬核"),
+        GameMode::Spectator => tr!("旁观"),
+        GameMode::Adventure => tr!("冒险"),
+    }
+}
+
+/// 单独输出的一行字段，`label` 为未翻译的字段名
+///
+/// 纯文本输出时为 `字段: 值`，字段名固定为英文，与 [`plain_lines`] 一致
+pub fn field_line(label: &'static str, value: ColoredString) -> String {
+    if plain_output() {
+        return format!("{}: {}", english(label), &*value);
+    }
+    let label = tr(label);
+    let padding = " ".repeat(label_width().saturating_sub(UnicodeWidthStr::width(label)));
+    format!("{}{} | {}", padding, label.bright_cyan(), value)
+}
+
+/// 所有字段名，用于计算字段名一列的宽度
+const FIELD_LABELS: &[&str] = &[
+    "Java版",
+    "基岩版",
+    "地址",
+    "在线玩家",
+    "延迟",
+    "地图",
+    "游戏模式",
+    "服务端",
+    "插件",
+    "玩家列表",
+    "图标",
+    "图片",
+    "提示",
+    "监视",
+    "人数变化",
+];
+
+/// 字段名一列的宽度，至少为 10，当前语言中有更长的字段名时随之加宽
+pub fn label_width() -> usize {
+    FIELD_LABELS
+        .iter()
+        .map(|&label| UnicodeWidthStr::width(tr(label)))
+        .max()
+        .unwrap_or(0)
+        .max(10)
+}
+
+pub fn output_field_format(field: &str) -> String {
+    format!(
+        "{}{}",
+        " ".repeat(label_width().saturating_sub(UnicodeWidthStr::width(field))),
+        field
+    )
+}
+
+fn to_colored_string(text: &str, edition: Edition) -> ColoredString {
+    spans_to_colored_string(&legacy_spans(text, &Style::default(), edition)).into()
+}
+
+fn spans_to_colored_string(spans: &[Span]) -> String {
+    let mut colored_string = String::new();
+    for span in spans {
+        // 逐行着色，避免换行符两侧的转义序列错位
+        let lines: Vec<String> = span
+            .text
+            .split('\n')
+            .map(|line| {
+                let mut text = line.normal();
+                if span.style.bold {
+                    text = text.bold();
+                }
+                if span.style.italic {
+                    text = text.italic();
+                }
+                if span.style.underlined {
+                    text = text.underline();
+                }
+                if span.style.strikethrough {
+                    text = text.strikethrough();
+                }
+                if span.style.obfuscated {
+                    text = text.dimmed();
+                }
+                paint(
+                    text,
+                    Some(span.style.color.unwrap_or((255, 255, 255))),
+                    None,
+                )
+            })
+            .collect();
+        colored_string.push_str(&lines.join("\n"));
+    }
+    colored_string
+}
+
+type PixelRowPair = (Vec<Rgba<u8>>, Option<Vec<Rgba<u8>>>);
+
+pub fn img2lines(buffer: &[u8], size: u32) -> Result<Vec<String>, Box<dyn Error>> {
+    let image = load_from_memory(buffer)?.resize(size, size, FilterType::CatmullRom);
+    let pixels = image.pixels().collect::<Vec<_>>();
+    let mut pixels_2d: Vec<Vec<Rgba<u8>>> = Vec::new();
+    for pixel in pixels {
+        let (x, y) = (pixel.0, pixel.1);
+        if x == 0 {
+            pixels_2d.push(Vec::new());
+        };
+        pixels_2d.last_mut().unwrap().push(image.get_pixel(x, y));
+    }
+    let pixel_2d_pairs: Vec<PixelRowPair> = pixels_2d
+        .chunks(2)
+        .map(|chunk| {
+            let row1 = chunk[0].clone();
+            let row2 = if chunk.len() > 1 {
+                Some(chunk[1].clone())
+            } else {
+                None
+            };
+            (row1, row2)
+        })
+        .collect();
+
+    let mut lines: Vec<String> = Vec::new();
+    for (row1, row2) in pixel_2d_pairs {
+        let mut line = String::new();
+        if let Some(row2) = row2 {
+            for i in 0..row1.len() - 1 {
+                let block = paint(
+                    "▀".normal(),
+                    Some((row1[i][0], row1[i][1], row1[i][2])),
+                    Some((row2[i][0], row2[i][1], row2[i][2])),
+                );
+                line = format!("{}{}", line, block);
+            }
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+fn calc_image_size(base: (u16, u16)) -> Result<usize, Box<dyn Error>> {
+    let term_size = crossterm::terminal::size().unwrap_or((80, 24));
+    if term_size.0 <= base.0 || term_size.1 <= base.1 {
+        return Err(tr!("控制台过小，请调大控制台窗口的大小").into());
+    }
+    let x_max = term_size.0 - base.0;
+    let y_max = term_size.1 - base.1;
+    if x_max < y_max {
+        if x_max < 13 {
+            return Err(tr!("控制台过小，请调大控制台窗口的大小").into());
+        }
+        Ok(x_max as usize)
+    } else {
+        Ok(if (y_max * 2 - 2) > 64 {
+            64
+        } else {
+            y_max * 2 - 2
+        } as usize)
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{
+    cli::split_address,
+    config::Config,
+    http,
+    json::{error_to_json, status_to_json},
+    render::field_line,
+};
+
+use colored::Colorize;
+use motd::{query, tr, QueryError, QueryOptions, ServerStatus};
+use serde_json::{json, Value};
+use tiny_http::{Method, Request};
+
+use std::{
+    collections::HashMap,
+    process::exit,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+/// 在上游查询的并发数之外，额外用于处理命中缓存的请求的线程数
+const CACHE_WORKERS: usize = 8;
+
+type Key = (String, Option<u16>);
+type QueryResult = Arc<Result<ServerStatus, QueryError>>;
+
+/// 按地址缓存查询结果，失败的结果同样缓存，避免反复查询离线的服务器
+struct Cache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Key, (Instant, QueryResult)>>,
+}
+
+impl Cache {
+    fn get(&self, key: &Key) -> Option<QueryResult> {
+        let entries = self.entries.lock().unwrap();
+        let (time, result) = entries.get(key)?;
+        (time.elapsed() < self.ttl).then(|| result.clone())
+    }
+
+    fn insert(&self, key: Key, result: QueryResult) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (time, _)| time.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), result));
+    }
+}
+
+/// 限制同时进行的上游查询数，超出时等待
+struct Limit {
+    max: usize,
+    running: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Limit {
+    fn acquire(&self) -> Permit<'_> {
+        let mut running = self.running.lock().unwrap();
+        while *running >= self.max {
+            running = self.released.wait(running).unwrap();
+        }
+        *running += 1;
+        Permit(self)
+    }
+}
+
+struct Permit<'a>(&'a Limit);
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        *self.0.running.lock().unwrap() -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+/// 一次进行中的上游查询，相同地址的请求等待它的结果而不再重复查询
+#[derive(Default)]
+struct Flight {
+    result: Mutex<Option<QueryResult>>,
+    finished: Condvar,
+}
+
+impl Flight {
+    fn wait(&self) -> QueryResult {
+        let mut result = self.result.lock().unwrap();
+        loop {
+            match &*result {
+                Some(result) => return result.clone(),
+                None => result = self.finished.wait(result).unwrap(),
+            }
+        }
+    }
+
+    fn finish(&self, result: QueryResult) {
+        *self.result.lock().unwrap() = Some(result);
+        self.finished.notify_all();
+    }
+}
+
+struct State<'a> {
+    cache: Cache,
+    limit: Limit,
+    in_flight: Mutex<HashMap<Key, Arc<Flight>>>,
+    options: &'a QueryOptions,
+    config: &'a Config,
+}
+
+/// 提供 `/status/<地址>` 与 `/favicon/<地址>`，地址的写法与命令行相同
+pub fn run(listen: &str, ttl: Duration, parallel: usize, options: &QueryOptions, config: &Config) {
+    let server = tiny_http::Server::http(listen).unwrap_or_else(|e| {
+        println!(
+            "{}\n{}",
+            tr!("无法监听 {}", listen).bright_red().bold(),
+            e.to_string().bright_red()
+        );
+        exit(1);
+    });
+    println!(
+        "{}",
+        field_line(
+            "监听",
+            format!("http://{}/status/<host:port>", listen).bright_cyan()
+        )
+    );
+    println!(
+        "{}",
+        field_line("缓存时间", format!("{}s", ttl.as_secs()).bright_green())
+    );
+
+    let state = State {
+        cache: Cache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        },
+        limit: Limit {
+            max: parallel,
+            running: Mutex::new(0),
+            released: Condvar::new(),
+        },
+        in_flight: Mutex::new(HashMap::new()),
+        options,
+        config,
+    };
+    http::serve(&server, parallel + CACHE_WORKERS, |request| {
+        handle(request, &state)
+    });
+}
+
+fn handle(request: Request, state: &State) {
+    if *request.method() != Method::Get {
+        let body = json!({ "error": "method not allowed" });
+        return respond_json(request, 405, &body, 0);
+    }
+    let url = request.url().to_string();
+    let path = url.split('?').next().unwrap_or_default();
+    let (route, target) = match path.trim_start_matches('/').split_once('/') {
+        Some((route @ ("status" | "favicon"), target)) if !target.is_empty() => (route, target),
+        _ => {
+            let body = json!({ "error": "not found" });
+            return respond_json(request, 404, &body, 0);
+        }
+    };
+    let target = percent_decode(target);
+    let Some(key) = split_address(state.config.resolve(&target)) else {
+        let body = json!({ "error": "invalid address" });
+        return respond_json(request, 400, &body, 0);
+    };
+
+    let result = lookup(state, key);
+    let max_age = state.cache.ttl.as_secs();
+    match (route, &*result) {
+        ("status", Ok(status)) => {
+            let mut body = status_to_json(&target, status);
+            if status.favicon.is_some() {
+                body["favicon_url"] = Value::from(format!("/favicon/{}", target));
+            }
+            respond_json(request, 200, &body, max_age)
+        }
+        (_, Err(e)) => {
+            let body = error_to_json(&target, e);
+            respond_json(request, http_status(e), &body, max_age)
+        }
+        (_, Ok(status)) => match status.favicon_png() {
+            Some(Ok(png)) => respond(request, 200, "image/png", png, max_age),
+            Some(Err(e)) => {
+                let body = json!({ "error": e });
+                respond_json(request, 502, &body, max_age)
+            }
+            None => {
+                let body = json!({ "error": "no favicon" });
+                respond_json(request, 404, &body, max_age)
+            }
+        },
+    }
+}
+
+/// 优先使用缓存，未命中时在并发限制内查询，同时未命中的相同地址只查询一次
+fn lookup(state: &State, key: Key) -> QueryResult {
+    if let Some(result) = state.cache.get(&key) {
+        return result;
+    }
+    let flight = {
+        let mut in_flight = state.in_flight.lock().unwrap();
+        if let Some(flight) = in_flight.get(&key) {
+            let flight = flight.clone();
+            drop(in_flight);
+            return flight.wait();
+        }
+        // 查询完成时先写入缓存再移出进行中的查询，持有锁时再检查一次即可避免重复查询
+        if let Some(result) = state.cache.get(&key) {
+            return result;
+        }
+        let flight = Arc::new(Flight::default());
+        in_flight.insert(key.clone(), flight.clone());
+        flight
+    };
+
+    let options = QueryOptions {
+        port: key.1,
+        ..state.options.clone()
+    };
+    let result = {
+        let _permit = state.limit.acquire();
+        Arc::new(query(&key.0, &options))
+    };
+    state.cache.insert(key.clone(), result.clone());
+    state.in_flight.lock().unwrap().remove(&key);
+    flight.finish(result.clone());
+    result
+}
+
+/// 超时为 504，其余上游错误均为 502
+fn http_status(error: &QueryError) -> u16 {
+    match error {
+        QueryError::Timeout => 504,
+        QueryError::Resolve(_) | QueryError::Connect(_) | QueryError::Protocol(_) => 502,
+    }
+}
+
+fn respond_json(request: Request, status: u16, body: &Value, max_age: u64) {
+    let body = serde_json::to_vec_pretty(body).unwrap();
+    respond(request, status, "application/json", body, max_age);
+}
+
+fn respond(request: Request, status: u16, content_type: &str, body: Vec<u8>, max_age: u64) {
+    let cache_control = format!("max-age={}", max_age);
+    let headers = [
+        ("Content-Type", content_type),
+        ("Cache-Control", &cache_control),
+        // 供网页前端直接跨域调用
+        ("Access-Control-Allow-Origin", "*"),
+    ];
+    http::respond(request, status, &headers, body);
+}
+
+/// 解码路径中的 `%XX`，IPv6 地址的方括号通常会被编码
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
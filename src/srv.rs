@@ -0,0 +1,389 @@
+use crate::{
+    net::{connect_udp, invalid_data},
+    tr,
+};
+
+use std::{
+    collections::hash_map::RandomState,
+    fs,
+    hash::{BuildHasher, Hasher},
+    io,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+const TYPE_SRV: u16 = 33;
+const CLASS_IN: u16 = 1;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 每次请求的等待时间，UDP 数据包丢失时尽快重试
+const ATTEMPT_TIMEOUT: Duration = Duration::from_millis(1500);
+/// 每个 DNS 服务器最多请求的次数
+const ATTEMPTS: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+}
+
+/// 查询 `_minecraft._tcp.<host>` 并按优先级与权重选出一条记录
+///
+/// `nameserver` 为空时使用系统配置的 DNS 服务器，系统中没有 `/etc/resolv.conf` (如 Windows)
+/// 时不查询 SRV 记录，直接返回 `None`。所有请求都超时时同样视为没有记录
+pub fn lookup_minecraft_srv(
+    host: &str,
+    nameserver: Option<SocketAddr>,
+) -> io::Result<Option<SrvRecord>> {
+    lookup_with_timeout(host, nameserver, DEFAULT_TIMEOUT)
+}
+
+/// `timeout` 为整个查询的时限，每次请求最多等待 [`ATTEMPT_TIMEOUT`]，超时后重试一次
+pub(crate) fn lookup_with_timeout(
+    host: &str,
+    nameserver: Option<SocketAddr>,
+    timeout: Duration,
+) -> io::Result<Option<SrvRecord>> {
+    let name = format!("_minecraft._tcp.{}", host.trim_end_matches('.'));
+    let nameservers = match nameserver {
+        Some(nameserver) => vec![nameserver],
+        None => system_nameservers(),
+    };
+
+    let deadline = Instant::now() + timeout;
+    let mut last_err = None;
+    'nameservers: for nameserver in nameservers {
+        for _ in 0..ATTEMPTS {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break 'nameservers;
+            }
+            match query_srv(&name, nameserver, remaining.min(ATTEMPT_TIMEOUT)) {
+                Ok(records) => return Ok(select(records)),
+                Err(e) if is_timeout(&e) => last_err = Some(e),
+                Err(e) => {
+                    last_err = Some(e);
+                    continue 'nameservers;
+                }
+            }
+        }
+    }
+    match last_err {
+        // 超时视为没有 SRV 记录，由调用方直接连接域名的 A/AAAA 地址
+        Some(e) if !is_timeout(&e) => Err(e),
+        // 不知道系统的 DNS 服务器时跳过 SRV，避免将查询发往第三方服务器
+        _ => Ok(None),
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+fn system_nameservers() -> Vec<SocketAddr> {
+    fs::read_to_string("/etc/resolv.conf")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("nameserver"), Some(ip)) => ip.parse().ok().map(|ip| SocketAddr::new(ip, 53)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn query_srv(name: &str, nameserver: SocketAddr, timeout: Duration) -> io::Result<Vec<SrvRecord>> {
+    let socket = connect_udp(nameserver, timeout)?;
+
+    let id = random() as u16;
+    socket.send(&build_query(id, name)?)?;
+
+    let mut buf = [0u8; 4096];
+    loop {
+        let len = socket.recv(&mut buf)?;
+        let resp = &buf[..len];
+        if resp.len() >= 2 && u16::from_be_bytes([resp[0], resp[1]]) == id {
+            return parse_response(resp);
+        }
+    }
+}
+
+fn build_query(id: u16, name: &str) -> io::Result<Vec<u8>> {
+    let mut packet = Vec::with_capacity(name.len() + 18);
+    packet.extend_from_slice(&id.to_be_bytes());
+    // 标准查询，期望递归
+    packet.extend_from_slice(&[0x01, 0x00]);
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(invalid_data(tr!("不合法的域名")));
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_SRV.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(packet)
+}
+
+fn parse_response(resp: &[u8]) -> io::Result<Vec<SrvRecord>> {
+    if resp.len() < 12 {
+        return Err(invalid_data(tr!("DNS 响应过短")));
+    }
+    match resp[3] & 0x0f {
+        0 => {}
+        // NXDOMAIN 即没有 SRV 记录
+        3 => return Ok(Vec::new()),
+        rcode => return Err(invalid_data(&tr!("DNS 服务器返回错误 {}", rcode))),
+    }
+    let qdcount = u16::from_be_bytes([resp[4], resp[5]]);
+    let ancount = u16::from_be_bytes([resp[6], resp[7]]);
+
+    let mut pos = 12;
+    for _ in 0..qdcount {
+        pos = skip_name(resp, pos)? + 4;
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..ancount {
+        pos = skip_name(resp, pos)?;
+        let header = resp
+            .get(pos..pos + 10)
+            .ok_or_else(|| invalid_data(tr!("DNS 响应不完整")))?;
+        let rtype = u16::from_be_bytes([header[0], header[1]]);
+        let rdlength = u16::from_be_bytes([header[8], header[9]]) as usize;
+        pos += 10;
+        let rdata = resp
+            .get(pos..pos + rdlength)
+            .ok_or_else(|| invalid_data(tr!("DNS 响应不完整")))?;
+        if rtype == TYPE_SRV && rdata.len() > 6 {
+            records.push(SrvRecord {
+                priority: u16::from_be_bytes([rdata[0], rdata[1]]),
+                weight: u16::from_be_bytes([rdata[2], rdata[3]]),
+                port: u16::from_be_bytes([rdata[4], rdata[5]]),
+                target: read_name(resp, pos + 6)?,
+            });
+        }
+        pos += rdlength;
+    }
+    Ok(records)
+}
+
+fn skip_name(resp: &[u8], mut pos: usize) -> io::Result<usize> {
+    loop {
+        let len = *resp
+            .get(pos)
+            .ok_or_else(|| invalid_data(tr!("DNS 响应不完整")))?;
+        if len & 0xc0 == 0xc0 {
+            return Ok(pos + 2);
+        }
+        if len == 0 {
+            return Ok(pos + 1);
+        }
+        pos += len as usize + 1;
+    }
+}
+
+fn read_name(resp: &[u8], mut pos: usize) -> io::Result<String> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut jumps = 0;
+    loop {
+        let len = *resp
+            .get(pos)
+            .ok_or_else(|| invalid_data(tr!("DNS 响应不完整")))?;
+        if len & 0xc0 == 0xc0 {
+            let low = *resp
+                .get(pos + 1)
+                .ok_or_else(|| invalid_data(tr!("DNS 响应不完整")))?;
+            jumps += 1;
+            if jumps > 16 {
+                return Err(invalid_data(tr!("DNS 响应中存在循环压缩指针")));
+            }
+            pos = (((len & 0x3f) as usize) << 8) | low as usize;
+            continue;
+        }
+        if len == 0 {
+            return Ok(labels.join("."));
+        }
+        let label = resp
+            .get(pos + 1..pos + 1 + len as usize)
+            .ok_or_else(|| invalid_data(tr!("DNS 响应不完整")))?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += len as usize + 1;
+    }
+}
+
+/// RFC 2782: 取优先级最小的一组，再按权重随机选择
+fn select(mut records: Vec<SrvRecord>) -> Option<SrvRecord> {
+    // 目标为 "." 表示该服务明确不可用
+    records.retain(|record| !record.target.is_empty());
+    let priority = records.iter().map(|record| record.priority).min()?;
+    let mut candidates: Vec<SrvRecord> = records
+        .into_iter()
+        .filter(|record| record.priority == priority)
+        .collect();
+
+    let total: u64 = candidates.iter().map(|record| record.weight as u64).sum();
+    if total == 0 {
+        let index = (random() % candidates.len() as u64) as usize;
+        return Some(candidates.swap_remove(index));
+    }
+    let mut pick = random() % total;
+    for (index, record) in candidates.iter().enumerate() {
+        if pick < record.weight as u64 {
+            return Some(candidates.swap_remove(index));
+        }
+        pick -= record.weight as u64;
+    }
+    candidates.pop()
+}
+
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{net::UdpSocket, thread};
+
+    fn srv_answer(priority: u16, weight: u16, port: u16, target: &[u8]) -> Vec<u8> {
+        // 名称指向问题部分的 _minecraft._tcp.example.com
+        let mut answer = vec![0xc0, 12];
+        answer.extend_from_slice(&TYPE_SRV.to_be_bytes());
+        answer.extend_from_slice(&CLASS_IN.to_be_bytes());
+        answer.extend_from_slice(&300u32.to_be_bytes());
+        answer.extend_from_slice(&(6 + target.len() as u16).to_be_bytes());
+        answer.extend_from_slice(&priority.to_be_bytes());
+        answer.extend_from_slice(&weight.to_be_bytes());
+        answer.extend_from_slice(&port.to_be_bytes());
+        answer.extend_from_slice(target);
+        answer
+    }
+
+    /// 以 `rcode` 与 `answers` 回应一次查询的本地 DNS 服务器
+    fn stub_server(rcode: u8, answers: Vec<Vec<u8>>) -> SocketAddr {
+        lossy_server(0, rcode, answers)
+    }
+
+    /// 丢弃前 `dropped` 个请求后才回应的 DNS 服务器，模拟 UDP 丢包
+    fn lossy_server(dropped: usize, rcode: u8, answers: Vec<Vec<u8>>) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            for _ in 0..dropped {
+                socket.recv_from(&mut buf).unwrap();
+            }
+            let (len, peer) = socket.recv_from(&mut buf).unwrap();
+            let mut resp = buf[..len].to_vec();
+            resp[2] = 0x81;
+            resp[3] = 0x80 | rcode;
+            resp[6..8].copy_from_slice(&(answers.len() as u16).to_be_bytes());
+            for answer in answers {
+                resp.extend_from_slice(&answer);
+            }
+            socket.send_to(&resp, peer).unwrap();
+        });
+        addr
+    }
+
+    #[test]
+    fn lookup_retries_lost_query() {
+        let nameserver = lossy_server(1, 0, vec![srv_answer(0, 0, 25566, b"\x02mc\xc0\x1c")]);
+        let record = lookup_with_timeout("example.com", Some(nameserver), Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.port, 25566);
+    }
+
+    #[test]
+    fn silent_nameserver_means_no_record() {
+        // 绑定后不读取，请求全部超时
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let started = Instant::now();
+        let record = lookup_with_timeout(
+            "example.com",
+            Some(socket.local_addr().unwrap()),
+            Duration::from_secs(10),
+        );
+        assert!(matches!(record, Ok(None)));
+        assert!(started.elapsed() < ATTEMPT_TIMEOUT * ATTEMPTS as u32 + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn lookup_prefers_lowest_priority() {
+        let nameserver = stub_server(
+            0,
+            vec![
+                srv_answer(20, 0, 25566, b"\x06backup\xc0\x1c"),
+                // 目标名称压缩为 mc + 问题中的 example.com
+                srv_answer(10, 5, 25565, b"\x02mc\xc0\x1c"),
+            ],
+        );
+        let record = lookup_with_timeout("example.com", Some(nameserver), Duration::from_secs(2))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            record,
+            SrvRecord {
+                priority: 10,
+                weight: 5,
+                port: 25565,
+                target: "mc.example.com".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn lookup_nxdomain_is_none() {
+        let nameserver = stub_server(3, Vec::new());
+        let record = lookup_with_timeout("example.com", Some(nameserver), Duration::from_secs(2));
+        assert_eq!(record.unwrap(), None);
+    }
+
+    #[test]
+    fn lookup_ignores_unavailable_target() {
+        let nameserver = stub_server(0, vec![srv_answer(0, 0, 25565, b"\x00")]);
+        let record = lookup_with_timeout("example.com", Some(nameserver), Duration::from_secs(2));
+        assert_eq!(record.unwrap(), None);
+    }
+
+    #[test]
+    fn lookup_server_failure_is_error() {
+        let nameserver = stub_server(2, Vec::new());
+        let record = lookup_with_timeout("example.com", Some(nameserver), Duration::from_secs(2));
+        assert!(record.is_err());
+    }
+
+    #[test]
+    fn parse_truncated_response() {
+        let mut resp = build_query(1, "_minecraft._tcp.example.com").unwrap();
+        resp[7] = 1;
+        let answer = srv_answer(0, 0, 25565, b"\x02mc\xc0\x1c");
+        for len in 0..answer.len() {
+            let mut truncated = resp.clone();
+            truncated.extend_from_slice(&answer[..len]);
+            assert!(parse_response(&truncated).is_err(), "{} 字节", len);
+        }
+        assert!(parse_response(&resp[..6]).is_err());
+    }
+
+    #[test]
+    fn parse_compression_loop() {
+        let mut resp = build_query(1, "_minecraft._tcp.example.com").unwrap();
+        resp[7] = 1;
+        let target_pos = resp.len() + 18;
+        let pointer = [0xc0 | (target_pos >> 8) as u8, target_pos as u8];
+        resp.extend_from_slice(&srv_answer(0, 0, 25565, &pointer));
+        assert!(parse_response(&resp).is_err());
+    }
+}
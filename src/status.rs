@@ -0,0 +1,254 @@
+use crate::{
+    bedrock::BedrockPing,
+    error::QueryError,
+    gamespy::FullStat,
+    java::JavaPing,
+    legacy::LegacyPing,
+    srv::SrvRecord,
+    text::{legacy_spans, Span, Style, TextComponent},
+    tr,
+};
+
+use base64::prelude::*;
+use serde_json::Value;
+
+use std::{net::SocketAddr, time::Duration};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edition {
+    Java,
+    Bedrock,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Hardcore,
+    Spectator,
+    Adventure,
+}
+
+/// 与版本无关的服务器状态
+#[derive(Debug, Clone)]
+pub struct ServerStatus {
+    pub edition: Edition,
+    pub version: String,
+    pub protocol: i32,
+    /// Java 版为原始的 description JSON，基岩版为带 `§` 格式代码的文本
+    pub motd: String,
+    pub players_online: u32,
+    pub players_max: u32,
+    pub players: Vec<String>,
+    pub map: Option<String>,
+    pub game_mode: Option<GameMode>,
+    /// `data:image/png;base64,` 形式的服务器图标
+    pub favicon: Option<String>,
+    /// 实际响应查询的地址
+    pub address: SocketAddr,
+    /// Java 版经由 SRV 记录重定向时的目标
+    pub srv: Option<SrvRecord>,
+    /// 由 ping 数据包测得的往返延迟
+    pub latency: Option<Duration>,
+    /// Java 版服务器返回的完整状态 JSON
+    pub raw: Option<Value>,
+    /// 启用 GameSpy4 查询时的结果，查询失败时为错误信息
+    pub full_stat: Option<Result<FullStat, String>>,
+}
+
+/// [`query_both`](crate::query_both) 的结果
+#[derive(Debug)]
+pub struct BothStatus {
+    pub java: Result<ServerStatus, QueryError>,
+    pub bedrock: Result<ServerStatus, QueryError>,
+}
+
+impl BothStatus {
+    /// 两个版本的 Motd 或人数完全一致时，很可能是通过 Geyser 互通的同一服务器
+    pub fn likely_geyser(&self) -> bool {
+        let (Ok(java), Ok(bedrock)) = (&self.java, &self.bedrock) else {
+            return false;
+        };
+        let first_line = |status: &ServerStatus| {
+            status
+                .motd_plain()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let same_motd = !first_line(java).is_empty() && first_line(java) == first_line(bedrock);
+        let same_players = java.players_max > 0
+            && (java.players_online, java.players_max)
+                == (bedrock.players_online, bedrock.players_max);
+        same_motd || same_players
+    }
+}
+
+impl ServerStatus {
+    pub(crate) fn from_java(ping: JavaPing, address: SocketAddr) -> Self {
+        let raw = ping.raw;
+        ServerStatus {
+            edition: Edition::Java,
+            version: raw["version"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            protocol: raw["version"]["protocol"].as_i64().unwrap_or(-1) as i32,
+            motd: raw["description"].to_string(),
+            players_online: raw["players"]["online"].as_u64().unwrap_or_default() as u32,
+            players_max: raw["players"]["max"].as_u64().unwrap_or_default() as u32,
+            players: raw["players"]["sample"]
+                .as_array()
+                .map(|sample| {
+                    sample
+                        .iter()
+                        .filter_map(|player| player["name"].as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            map: None,
+            game_mode: None,
+            favicon: raw["favicon"].as_str().map(str::to_string),
+            address,
+            srv: None,
+            latency: ping.latency,
+            raw: Some(raw),
+            full_stat: None,
+        }
+    }
+
+    /// 旧版服务器的 Motd 为带 `§` 格式代码的文本，转为 JSON 字符串后与新版统一处理
+    pub(crate) fn from_legacy(ping: LegacyPing, address: SocketAddr) -> Self {
+        ServerStatus {
+            edition: Edition::Java,
+            version: ping.version,
+            protocol: ping.protocol,
+            motd: Value::String(ping.motd).to_string(),
+            players_online: ping.players_online,
+            players_max: ping.players_max,
+            players: Vec::new(),
+            map: None,
+            game_mode: None,
+            favicon: None,
+            address,
+            srv: None,
+            latency: Some(ping.latency),
+            raw: None,
+            full_stat: None,
+        }
+    }
+
+    pub(crate) fn from_bedrock(ping: BedrockPing, address: SocketAddr) -> Self {
+        ServerStatus {
+            edition: Edition::Bedrock,
+            version: ping.version,
+            protocol: ping.protocol,
+            motd: ping.motd,
+            players_online: ping.players_online,
+            players_max: ping.players_max,
+            players: Vec::new(),
+            map: ping.map,
+            game_mode: ping.game_mode,
+            favicon: None,
+            address,
+            srv: None,
+            latency: Some(ping.latency),
+            raw: None,
+            full_stat: None,
+        }
+    }
+
+    /// 将 Motd 解析为文本组件，基岩版的 Motd 视为单个文本组件
+    pub fn motd_component(&self) -> TextComponent {
+        match self.edition {
+            Edition::Java => {
+                TextComponent::parse(&self.motd).unwrap_or_else(|_| TextComponent::text(&self.motd))
+            }
+            Edition::Bedrock => TextComponent::text(&self.motd),
+        }
+    }
+
+    /// 按对应版本的格式代码将 Motd 展开为带样式的文本片段
+    pub fn motd_spans(&self) -> Vec<Span> {
+        match self.edition {
+            Edition::Java => self.motd_component().spans(),
+            Edition::Bedrock => legacy_spans(&self.motd, &Style::default(), Edition::Bedrock),
+        }
+    }
+
+    /// 解码服务器图标得到 PNG 数据，没有图标时为 `None`
+    pub fn favicon_png(&self) -> Option<Result<Vec<u8>, String>> {
+        let favicon = self.favicon.as_ref()?;
+        let Some(data) = favicon.strip_prefix("data:image/png;base64,") else {
+            return Some(Err(tr!("图标不是 PNG 格式的 data URI").to_string()));
+        };
+        // 部分旧版服务端会在 base64 中插入换行
+        let data: String = data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+        Some(match BASE64_STANDARD.decode(data) {
+            Ok(png) if png.starts_with(PNG_SIGNATURE) => Ok(png),
+            Ok(_) => Err(tr!("图标内容不是 PNG 图片").to_string()),
+            Err(e) => Err(tr!("图标的 base64 数据不合法: {}", e)),
+        })
+    }
+
+    /// 去除所有格式后的 Motd 纯文本
+    pub fn motd_plain(&self) -> String {
+        self.motd_spans()
+            .into_iter()
+            .map(|span| span.text)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(edition: Edition, motd: &str) -> ServerStatus {
+        ServerStatus {
+            edition,
+            version: "1.20.51".to_string(),
+            protocol: 630,
+            motd: motd.to_string(),
+            players_online: 3,
+            players_max: 10,
+            players: Vec::new(),
+            map: None,
+            game_mode: None,
+            favicon: None,
+            address: "127.0.0.1:19132".parse().unwrap(),
+            srv: None,
+            latency: None,
+            raw: None,
+            full_stat: None,
+        }
+    }
+
+    #[test]
+    fn bedrock_plain_strips_material_colors() {
+        let status = status(Edition::Bedrock, "§gGold §uPurple §cRed");
+        assert_eq!(status.motd_plain(), "Gold Purple Red");
+    }
+
+    #[test]
+    fn java_plain_keeps_unknown_codes() {
+        let status = status(Edition::Java, r#"{"text":"§gGold §cRed"}"#);
+        assert_eq!(status.motd_plain(), "§gGold Red");
+    }
+
+    #[test]
+    fn geyser_compares_plain_motd() {
+        let both = BothStatus {
+            java: Ok(status(Edition::Java, r#"{"text":"Hello","color":"gold"}"#)),
+            bedrock: Ok(ServerStatus {
+                players_max: 20,
+                ..status(Edition::Bedrock, "§gHello")
+            }),
+        };
+        assert!(both.likely_geyser());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::cli::StoreFormat;
+
+use chrono::{DateTime, Local, SecondsFormat};
+use motd::tr;
+use rusqlite::{params, Connection, OpenFlags};
+
+use std::{
+    fs::{self, OpenOptions},
+    path::Path,
+};
+
+const CSV_HEADER: [&str; 7] = [
+    "time",
+    "server",
+    "online",
+    "players_online",
+    "players_max",
+    "latency_ms",
+    "version",
+];
+
+/// 一次查询的记录，离线时人数为 0，没有版本
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub time: DateTime<Local>,
+    pub server: String,
+    pub online: bool,
+    pub players_online: u32,
+    pub players_max: u32,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+}
+
+/// 读取时的筛选条件，均为 `None` 时读取全部记录
+#[derive(Default)]
+pub struct Filter {
+    pub server: Option<String>,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+}
+
+impl Filter {
+    fn matches(&self, record: &Record) -> bool {
+        self.server
+            .as_ref()
+            .is_none_or(|server| *server == record.server)
+            && self.since.is_none_or(|since| record.time >= since)
+            && self.until.is_none_or(|until| record.time <= until)
+    }
+}
+
+/// 追加一条记录，文件不存在时自动创建
+pub fn append(path: &Path, format: StoreFormat, record: &Record) -> Result<(), String> {
+    match format {
+        StoreFormat::Csv => append_csv(path, record).map_err(|e| e.to_string()),
+        StoreFormat::Sqlite => append_sqlite(path, record).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| tr!("无法写入 {}: {}", path.display(), e))
+}
+
+/// 按时间顺序读取符合条件的记录
+pub fn load(path: &Path, format: StoreFormat, filter: &Filter) -> Result<Vec<Record>, String> {
+    let records = match format {
+        StoreFormat::Csv => load_csv(path, filter),
+        StoreFormat::Sqlite => load_sqlite(path, filter).map_err(|e| e.to_string()),
+    };
+    let mut records = records.map_err(|e| tr!("无法读取 {}: {}", path.display(), e))?;
+    records.sort_by_key(|record| record.time);
+    Ok(records)
+}
+
+fn append_csv(path: &Path, record: &Record) -> Result<(), csv::Error> {
+    let new = fs::metadata(path).map_or(true, |metadata| metadata.len() == 0);
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut writer = csv::Writer::from_writer(file);
+    if new {
+        writer.write_record(CSV_HEADER)?;
+    }
+    writer.write_record([
+        record.time.to_rfc3339_opts(SecondsFormat::Secs, false),
+        record.server.clone(),
+        record.online.to_string(),
+        record.players_online.to_string(),
+        record.players_max.to_string(),
+        record
+            .latency_ms
+            .map(|latency| latency.to_string())
+            .unwrap_or_default(),
+        record.version.clone().unwrap_or_default(),
+    ])?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn load_csv(path: &Path, filter: &Filter) -> Result<Vec<Record>, String> {
+    let mut reader = csv::Reader::from_path(path).map_err(|e| e.to_string())?;
+    let mut records = Vec::new();
+    for (index, row) in reader.records().enumerate() {
+        let row = row.map_err(|e| e.to_string())?;
+        // 表头占第 1 行
+        let record = parse_csv_row(&row).ok_or_else(|| tr!("第 {} 行的格式不正确", index + 2))?;
+        if filter.matches(&record) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+fn parse_csv_row(row: &csv::StringRecord) -> Option<Record> {
+    let optional = |index: usize| row.get(index).filter(|value| !value.is_empty());
+    Some(Record {
+        time: DateTime::parse_from_rfc3339(row.get(0)?)
+            .ok()?
+            .with_timezone(&Local),
+        server: row.get(1)?.to_string(),
+        online: row.get(2)?.parse().ok()?,
+        players_online: row.get(3)?.parse().ok()?,
+        players_max: row.get(4)?.parse().ok()?,
+        latency_ms: optional(5).map(str::parse).transpose().ok()?,
+        version: optional(6).map(str::to_string),
+    })
+}
+
+/// 打开数据库，表不存在时创建
+fn open_sqlite(path: &Path) -> rusqlite::Result<Connection> {
+    let connection = Connection::open(path)?;
+    connection.execute_batch(
+        "CREATE TABLE IF NOT EXISTS records (
+            time INTEGER NOT NULL,
+            server TEXT NOT NULL,
+            online INTEGER NOT NULL,
+            players_online INTEGER NOT NULL,
+            players_max INTEGER NOT NULL,
+            latency_ms INTEGER,
+            version TEXT
+        );
+        CREATE INDEX IF NOT EXISTS records_server_time ON records (server, time);",
+    )?;
+    Ok(connection)
+}
+
+fn append_sqlite(path: &Path, record: &Record) -> rusqlite::Result<()> {
+    open_sqlite(path)?.execute(
+        "INSERT INTO records VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            record.time.timestamp(),
+            record.server,
+            record.online,
+            record.players_online,
+            record.players_max,
+            record.latency_ms.map(|latency| latency as i64),
+            record.version,
+        ],
+    )?;
+    Ok(())
+}
+
+fn load_sqlite(path: &Path, filter: &Filter) -> rusqlite::Result<Vec<Record>> {
+    // 只读打开，避免文件不存在时创建空数据库
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut statement = connection.prepare(
+        "SELECT time, server, online, players_online, players_max, latency_ms, version
+         FROM records
+         WHERE (?1 IS NULL OR server = ?1) AND time >= ?2 AND time <= ?3
+         ORDER BY time",
+    )?;
+    let rows = statement.query_map(
+        params![
+            filter.server,
+            filter.since.map_or(i64::MIN, |since| since.timestamp()),
+            filter.until.map_or(i64::MAX, |until| until.timestamp()),
+        ],
+        |row| {
+            let timestamp: i64 = row.get(0)?;
+            let latency: Option<i64> = row.get(5)?;
+            Ok(Record {
+                time: DateTime::from_timestamp(timestamp, 0)
+                    .unwrap_or_default()
+                    .with_timezone(&Local),
+                server: row.get(1)?,
+                online: row.get(2)?,
+                players_online: row.get(3)?,
+                players_max: row.get(4)?,
+                latency_ms: latency.map(|latency| latency as u64),
+                version: row.get(6)?,
+            })
+        },
+    )?;
+    rows.collect()
+}
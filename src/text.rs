@@ -0,0 +1,535 @@
+use crate::{
+    formatting::{
+        mc_formatting_colors_by_name, mc_formatting_colors_by_ss, mc_formatting_styles, ss,
+        MCFontFormattingStyle,
+    },
+    Edition,
+};
+
+use serde_json::{Map, Value};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// 展开后的最终样式，颜色为空时使用默认颜色
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub color: Option<(u8, u8, u8)>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+    pub obfuscated: bool,
+}
+
+/// 一段样式相同的文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    Text(String),
+    Translate {
+        key: String,
+        fallback: Option<String>,
+        with: Vec<TextComponent>,
+    },
+    Keybind(String),
+    Score {
+        name: String,
+        objective: String,
+        value: Option<String>,
+    },
+    Selector(String),
+}
+
+/// Java 版的 JSON 文本组件
+///
+/// 样式字段为空时继承父组件
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextComponent {
+    pub content: Content,
+    pub color: Option<(u8, u8, u8)>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    pub extra: Vec<TextComponent>,
+}
+
+impl TextComponent {
+    pub fn text(text: &str) -> Self {
+        TextComponent {
+            content: Content::Text(text.to_string()),
+            color: None,
+            bold: None,
+            italic: None,
+            underlined: None,
+            strikethrough: None,
+            obfuscated: None,
+            extra: Vec::new(),
+        }
+    }
+
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self::from_value(&serde_json::from_str(json)?))
+    }
+
+    /// 根组件可以是字符串、数组或对象
+    pub fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Object(map) => Self::from_object(map),
+            // 数组中第一个元素作为父组件，其余元素作为其子组件
+            Value::Array(values) => match values.split_first() {
+                Some((first, rest)) => {
+                    let mut component = Self::from_value(first);
+                    component.extra.extend(rest.iter().map(Self::from_value));
+                    component
+                }
+                None => Self::text(""),
+            },
+            Value::String(text) => Self::text(text),
+            Value::Null => Self::text(""),
+            other => Self::text(&other.to_string()),
+        }
+    }
+
+    fn from_object(map: &Map<String, Value>) -> Self {
+        let content = if let Some(text) = map.get("text") {
+            Content::Text(match text {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+        } else if let Some(Value::String(key)) = map.get("translate") {
+            Content::Translate {
+                key: key.clone(),
+                fallback: map
+                    .get("fallback")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                with: map
+                    .get("with")
+                    .and_then(Value::as_array)
+                    .map(|with| with.iter().map(Self::from_value).collect())
+                    .unwrap_or_default(),
+            }
+        } else if let Some(Value::String(keybind)) = map.get("keybind") {
+            Content::Keybind(keybind.clone())
+        } else if let Some(Value::Object(score)) = map.get("score") {
+            let field = |name: &str| score.get(name).and_then(Value::as_str).map(str::to_string);
+            Content::Score {
+                name: field("name").unwrap_or_default(),
+                objective: field("objective").unwrap_or_default(),
+                value: field("value"),
+            }
+        } else if let Some(Value::String(selector)) = map.get("selector") {
+            Content::Selector(selector.clone())
+        } else {
+            Content::Text(String::new())
+        };
+
+        let flag = |name: &str| match map.get(name) {
+            Some(Value::Bool(flag)) => Some(*flag),
+            Some(Value::String(flag)) => flag.parse().ok(),
+            _ => None,
+        };
+        TextComponent {
+            content,
+            color: map
+                .get("color")
+                .and_then(Value::as_str)
+                .and_then(parse_color),
+            bold: flag("bold"),
+            italic: flag("italic"),
+            underlined: flag("underlined"),
+            strikethrough: flag("strikethrough"),
+            obfuscated: flag("obfuscated"),
+            extra: match map.get("extra") {
+                Some(Value::Array(extra)) => extra.iter().map(Self::from_value).collect(),
+                Some(extra) => vec![Self::from_value(extra)],
+                None => Vec::new(),
+            },
+        }
+    }
+
+    /// 按从父到子的顺序展开为带样式的文本片段
+    pub fn spans(&self) -> Vec<Span> {
+        let mut spans = Vec::new();
+        self.collect_spans(&Style::default(), &mut spans);
+        spans
+    }
+
+    /// 去除所有样式后的纯文本
+    pub fn plain_text(&self) -> String {
+        self.spans().into_iter().map(|span| span.text).collect()
+    }
+
+    fn collect_spans(&self, parent: &Style, spans: &mut Vec<Span>) {
+        let style = Style {
+            color: self.color.or(parent.color),
+            bold: self.bold.unwrap_or(parent.bold),
+            italic: self.italic.unwrap_or(parent.italic),
+            underlined: self.underlined.unwrap_or(parent.underlined),
+            strikethrough: self.strikethrough.unwrap_or(parent.strikethrough),
+            obfuscated: self.obfuscated.unwrap_or(parent.obfuscated),
+        };
+        match &self.content {
+            Content::Text(text) => spans.extend(legacy_spans(text, &style, Edition::Java)),
+            Content::Translate {
+                key,
+                fallback,
+                with,
+            } => translate_spans(fallback.as_deref().unwrap_or(key), with, &style, spans),
+            Content::Keybind(keybind) => {
+                spans.extend(legacy_spans(keybind_name(keybind), &style, Edition::Java))
+            }
+            Content::Score { value, .. } => spans.extend(legacy_spans(
+                value.as_deref().unwrap_or(""),
+                &style,
+                Edition::Java,
+            )),
+            Content::Selector(selector) => {
+                spans.extend(legacy_spans(selector, &style, Edition::Java))
+            }
+        }
+        for extra in &self.extra {
+            extra.collect_spans(&style, spans);
+        }
+    }
+}
+
+/// 以 `base` 为初始样式，按 `edition` 的格式代码解析带 `§` 的文本
+pub fn legacy_spans(text: &str, base: &Style, edition: Edition) -> Vec<Span> {
+    let colors = mc_formatting_colors_by_ss(edition);
+    let styles = mc_formatting_styles(edition);
+    let mut spans = Vec::new();
+    let mut style = *base;
+    let mut buffer = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let code = match chars.peek() {
+            Some(next) if c == ss() => next.to_ascii_lowercase(),
+            _ => {
+                buffer.push(c);
+                continue;
+            }
+        };
+        if !colors.contains_key(&code) && !styles.contains_key(&code) {
+            buffer.push(c);
+            continue;
+        }
+        chars.next();
+        if !buffer.is_empty() {
+            spans.push(Span {
+                text: std::mem::take(&mut buffer),
+                style,
+            });
+        }
+        if let Some(&color) = colors.get(&code) {
+            // 与原版一致，颜色代码会清除之前的格式
+            style = Style {
+                color: Some(color),
+                ..Style::default()
+            };
+        } else if let Some(format) = styles.get(&code) {
+            match format {
+                MCFontFormattingStyle::Obfuscated => style.obfuscated = true,
+                MCFontFormattingStyle::Bold => style.bold = true,
+                MCFontFormattingStyle::Strikethrough => style.strikethrough = true,
+                MCFontFormattingStyle::Underline => style.underlined = true,
+                MCFontFormattingStyle::Italic => style.italic = true,
+                MCFontFormattingStyle::Clear => style = *base,
+            }
+        }
+    }
+    if !buffer.is_empty() {
+        spans.push(Span {
+            text: buffer,
+            style,
+        });
+    }
+    spans
+}
+
+fn translate_spans(template: &str, with: &[TextComponent], style: &Style, spans: &mut Vec<Span>) {
+    let mut literal = String::new();
+    let mut next_arg = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            literal.push(c);
+            continue;
+        }
+        // 支持 %s、%1$s 与 %%
+        let mut index = String::new();
+        while let Some(&digit) = chars.peek() {
+            if !digit.is_ascii_digit() {
+                break;
+            }
+            index.push(digit);
+            chars.next();
+        }
+        if index.is_empty() && chars.peek() == Some(&'%') {
+            chars.next();
+            literal.push('%');
+            continue;
+        }
+        if !index.is_empty() && chars.peek() == Some(&'$') {
+            chars.next();
+        }
+        if chars.peek() != Some(&'s') {
+            literal.push('%');
+            literal.push_str(&index);
+            continue;
+        }
+        chars.next();
+        let arg = match index.parse::<usize>() {
+            Ok(position) => position.checked_sub(1),
+            Err(_) => {
+                next_arg += 1;
+                Some(next_arg - 1)
+            }
+        };
+        spans.extend(legacy_spans(
+            &std::mem::take(&mut literal),
+            style,
+            Edition::Java,
+        ));
+        if let Some(arg) = arg.and_then(|arg| with.get(arg)) {
+            arg.collect_spans(style, spans);
+        }
+    }
+    spans.extend(legacy_spans(&literal, style, Edition::Java));
+}
+
+/// 原版默认按键绑定的显示名称
+fn keybind_name(keybind: &str) -> &str {
+    match keybind {
+        "key.forward" => "W",
+        "key.left" => "A",
+        "key.back" => "S",
+        "key.right" => "D",
+        "key.jump" => "Space",
+        "key.sneak" => "Left Shift",
+        "key.sprint" => "Left Control",
+        "key.inventory" => "E",
+        "key.swapOffhand" => "F",
+        "key.drop" => "Q",
+        "key.use" => "Right Button",
+        "key.attack" => "Left Button",
+        "key.pickItem" => "Middle Button",
+        "key.chat" => "T",
+        "key.playerlist" => "Tab",
+        "key.command" => "/",
+        "key.screenshot" => "F2",
+        "key.togglePerspective" => "F5",
+        "key.fullscreen" => "F11",
+        "key.advancements" => "L",
+        other => other,
+    }
+}
+
+/// 解析颜色名称或 `#RRGGBB` 形式的颜色
+pub fn parse_color(color: &str) -> Option<(u8, u8, u8)> {
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let rgb = u32::from_str_radix(hex, 16).ok()?;
+        return Some(((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8));
+    }
+    if color == "reset" {
+        return Some((255, 255, 255));
+    }
+    mc_formatting_colors_by_name().get(color).copied()
+}
+
+/// 按显示宽度截断，超出 `max` 时以 `...` 结尾，结果的显示宽度不超过 `max`
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.width() <= max {
+        return text.to_string();
+    }
+    let (mut truncated, mut width) = (String::new(), 3);
+    for c in text.chars() {
+        width += c.width().unwrap_or(0);
+        if width > max {
+            break;
+        }
+        truncated.push(c);
+    }
+    format!("{}...", truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, style: Style) -> Span {
+        Span {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    fn colored(color: (u8, u8, u8)) -> Style {
+        Style {
+            color: Some(color),
+            ..Style::default()
+        }
+    }
+
+    #[test]
+    fn truncate_by_display_width() {
+        assert_eq!(truncate("Paper 1.20.4", 12), "Paper 1.20.4");
+        assert_eq!(truncate("Paper 1.20.4", 10), "Paper 1...");
+        // 中文字符占两列，放不下时整个舍去
+        assert_eq!(truncate("中文服务器名称", 10), "中文服...");
+        assert_eq!(truncate("中文服务器名称", 9), "中文服...");
+        assert_eq!(truncate("中文服务器名称", 8), "中文...");
+    }
+
+    #[test]
+    fn bare_string_root() {
+        let component = TextComponent::parse(r#""§aHello""#).unwrap();
+        assert_eq!(
+            component.spans(),
+            vec![span("Hello", colored((85, 255, 85)))]
+        );
+    }
+
+    #[test]
+    fn array_root_inherits_first_element() {
+        let component =
+            TextComponent::parse(r#"[{"text":"A","bold":true},"B",{"text":"C","bold":false}]"#)
+                .unwrap();
+        let bold = Style {
+            bold: true,
+            ..Style::default()
+        };
+        assert_eq!(
+            component.spans(),
+            vec![
+                span("A", bold),
+                span("B", bold),
+                span("C", Style::default())
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_inherits_parent_style() {
+        let component = TextComponent::parse(
+            r##"{"text":"A","color":"gold","extra":[{"text":"B","italic":true},{"text":"C","color":"#123456"}]}"##,
+        )
+        .unwrap();
+        let gold = colored((255, 170, 0));
+        assert_eq!(
+            component.spans(),
+            vec![
+                span("A", gold),
+                span(
+                    "B",
+                    Style {
+                        italic: true,
+                        ..gold
+                    }
+                ),
+                span("C", colored((0x12, 0x34, 0x56))),
+            ]
+        );
+    }
+
+    #[test]
+    fn translate_with_arguments() {
+        let component = TextComponent::parse(
+            r#"{"translate":"%2$s + %s = 100%%","with":[{"text":"x","color":"red"},"y"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            component.spans(),
+            vec![
+                span("y", Style::default()),
+                span(" + ", Style::default()),
+                span("x", colored((255, 85, 85))),
+                span(" = 100%", Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn translate_uses_fallback() {
+        let component = TextComponent::parse(
+            r#"{"translate":"missing.key","fallback":"Hi %s","with":["Steve"]}"#,
+        )
+        .unwrap();
+        assert_eq!(component.plain_text(), "Hi Steve");
+    }
+
+    #[test]
+    fn color_code_resets_formatting() {
+        let spans = legacy_spans("§l§oA§cB§rC", &Style::default(), Edition::Java);
+        assert_eq!(
+            spans,
+            vec![
+                span(
+                    "A",
+                    Style {
+                        bold: true,
+                        italic: true,
+                        ..Style::default()
+                    }
+                ),
+                span("B", colored((255, 85, 85))),
+                span("C", Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn java_m_and_n_are_styles() {
+        let spans = legacy_spans("§mA§nB", &Style::default(), Edition::Java);
+        assert_eq!(
+            spans,
+            vec![
+                span(
+                    "A",
+                    Style {
+                        strikethrough: true,
+                        ..Style::default()
+                    }
+                ),
+                span(
+                    "B",
+                    Style {
+                        strikethrough: true,
+                        underlined: true,
+                        ..Style::default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn bedrock_m_and_n_are_material_colors() {
+        let spans = legacy_spans("§mA§nB", &Style::default(), Edition::Bedrock);
+        assert_eq!(
+            spans,
+            vec![
+                span("A", colored((151, 22, 7))),
+                span("B", colored((180, 104, 77))),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_and_trailing_codes_are_kept() {
+        let spans = legacy_spans("§zA§", &Style::default(), Edition::Java);
+        assert_eq!(spans, vec![span("§zA§", Style::default())]);
+        let spans = legacy_spans("§gA", &Style::default(), Edition::Java);
+        assert_eq!(spans, vec![span("§gA", Style::default())]);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{
+    graphics::ImageProtocol,
+    render::{bedrock_motd_lines, java_motd_lines, output_field_format},
+};
+
+use colored::Colorize;
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute, queue,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use motd::{query, tr, Edition, QueryOptions};
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+const HISTORY_LEN: usize = 30;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub fn run(addr: &str, port: Option<u16>, interval: Duration, options: &QueryOptions) {
+    let mut stdout = io::stdout();
+    let result = terminal::enable_raw_mode()
+        .and_then(|_| execute!(stdout, EnterAlternateScreen, Hide))
+        .and_then(|_| watch_loop(addr, port, interval, options));
+    let _ = execute!(stdout, Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+
+    if let Err(e) = result {
+        println!(
+            "{}\n{}",
+            tr!("终端操作失败").bright_red().bold(),
+            e.to_string().bright_red()
+        );
+    }
+}
+
+fn watch_loop(
+    addr: &str,
+    port: Option<u16>,
+    interval: Duration,
+    options: &QueryOptions,
+) -> io::Result<()> {
+    let mut history: VecDeque<u32> = VecDeque::with_capacity(HISTORY_LEN);
+    let mut polls: usize = 0;
+    loop {
+        polls += 1;
+        let (tx, rx) = mpsc::channel();
+        let options = QueryOptions {
+            port,
+            ..options.clone()
+        };
+        let host = addr.to_string();
+        thread::spawn(move || {
+            let _ = tx.send(query(&host, &options));
+        });
+        let result = loop {
+            if let Ok(result) = rx.try_recv() {
+                break result;
+            }
+            if quit_requested(Duration::from_millis(100))? {
+                return Ok(());
+            }
+        };
+
+        let mut lines = vec![format!(
+            "{} | {} {}",
+            output_field_format(tr!("监视")).bright_green(),
+            addr.bright_yellow(),
+            tr!(
+                "第 {} 次查询 · 每 {} 秒刷新 · 按 q 退出",
+                polls,
+                interval.as_secs()
+            )
+            .cyan()
+        )];
+        match result {
+            Ok(status) => {
+                let previous = history.back().copied();
+                if history.len() == HISTORY_LEN {
+                    history.pop_front();
+                }
+                history.push_back(status.players_online);
+                lines.push(format!(
+                    "{} | {} {}",
+                    output_field_format(tr!("人数变化")).bright_cyan(),
+                    players_delta(previous, status.players_online),
+                    sparkline(&history).bright_green()
+                ));
+                lines.extend(match status.edition {
+                    // 原位刷新时图像协议的图片无法被清除，始终使用半块字符
+                    Edition::Java => java_motd_lines(&status, 2, ImageProtocol::HalfBlocks),
+                    Edition::Bedrock => bedrock_motd_lines(&status),
+                });
+            }
+            Err(e) => {
+                lines.push(format!(
+                    "{} | {}",
+                    output_field_format(tr!("人数变化")).bright_cyan(),
+                    sparkline(&history).bright_green()
+                ));
+                lines.push(format!(
+                    "{} | {}",
+                    output_field_format("Motd").bright_cyan(),
+                    tr!("获取失败: {}", e).bright_red().bold()
+                ));
+            }
+        }
+        draw(&lines)?;
+
+        let deadline = Instant::now() + interval;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            if quit_requested(remaining.min(Duration::from_millis(100)))? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn draw(lines: &[String]) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    queue!(stdout, MoveTo(0, 0))?;
+    for line in lines {
+        write!(stdout, "{}", line)?;
+        queue!(stdout, Clear(ClearType::UntilNewLine))?;
+        write!(stdout, "\r\n")?;
+    }
+    queue!(stdout, Clear(ClearType::FromCursorDown))?;
+    stdout.flush()
+}
+
+fn quit_requested(timeout: Duration) -> io::Result<bool> {
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+    Ok(match event::read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => {
+            matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+        }
+        _ => false,
+    })
+}
+
+fn players_delta(previous: Option<u32>, current: u32) -> String {
+    match previous {
+        None => "-".normal().to_string(),
+        Some(previous) if current > previous => format!("+{}", current - previous)
+            .bright_green()
+            .to_string(),
+        Some(previous) if current < previous => {
+            format!("-{}", previous - current).bright_red().to_string()
+        }
+        Some(_) => "±0".normal().to_string(),
+    }
+}
+
+fn sparkline(history: &VecDeque<u32>) -> String {
+    let (Some(min), Some(max)) = (history.iter().min(), history.iter().max()) else {
+        return String::new();
+    };
+    let range = (max - min).max(1) as f64;
+    history
+        .iter()
+        .map(|value| {
+            let level = ((value - min) as f64 / range * (SPARKS.len() - 1) as f64).round();
+            SPARKS[level as usize]
+        })
+        .collect()
+}